@@ -1,5 +1,9 @@
-use crate::{Errors, StakeManager};
+use crate::{
+    helper, EraStatus, Errors, FeeSplit, InstantUnstakeFeeMode, PendingConfig, Role, StakeManager,
+};
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+use staking_program;
 
 #[derive(Accounts)]
 pub struct TransferStakeManagerAdmin<'info> {
@@ -12,11 +16,25 @@ pub struct TransferStakeManagerAdmin<'info> {
     pub stake_manager: Box<Account<'info, StakeManager>>,
 }
 
+#[event]
+pub struct EventTransferStakeManagerAdmin {
+    pub stake_manager: Pubkey,
+    pub admin: Pubkey,
+    pub old_pending_admin: Pubkey,
+    pub new_pending_admin: Pubkey,
+}
+
 impl<'info> TransferStakeManagerAdmin<'info> {
     pub fn process(&mut self, new_admin: Pubkey) -> Result<()> {
+        let old_pending_admin = self.stake_manager.pending_admin;
         self.stake_manager.pending_admin = new_admin;
 
-        msg!("NewAdmin: {}", new_admin);
+        emit!(EventTransferStakeManagerAdmin {
+            stake_manager: self.stake_manager.key(),
+            admin: self.admin.key(),
+            old_pending_admin,
+            new_pending_admin: new_admin,
+        });
         Ok(())
     }
 }
@@ -32,18 +50,70 @@ pub struct AcceptStakeManagerAdmin<'info> {
     pub stake_manager: Box<Account<'info, StakeManager>>,
 }
 
+#[event]
+pub struct EventAcceptStakeManagerAdmin {
+    pub stake_manager: Pubkey,
+    pub pending_admin: Pubkey,
+    pub old_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
+
 impl<'info> AcceptStakeManagerAdmin<'info> {
     pub fn process(&mut self) -> Result<()> {
+        let old_admin = self.stake_manager.admin;
         self.stake_manager.admin = self.stake_manager.pending_admin;
         self.stake_manager.pending_admin = Pubkey::default();
 
-        msg!("AcceptAdmin: {}", self.stake_manager.admin);
+        emit!(EventAcceptStakeManagerAdmin {
+            stake_manager: self.stake_manager.key(),
+            pending_admin: self.pending_admin.key(),
+            old_admin,
+            new_admin: self.stake_manager.admin,
+        });
         Ok(())
     }
 }
 
 #[derive(Accounts)]
-pub struct ConfigStakeManager<'info> {
+pub struct CancelAdminTransfer<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = admin @ Errors::AdminNotMatch
+    )]
+    pub stake_manager: Box<Account<'info, StakeManager>>,
+}
+
+#[event]
+pub struct EventCancelAdminTransfer {
+    pub stake_manager: Pubkey,
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+impl<'info> CancelAdminTransfer<'info> {
+    pub fn process(&mut self) -> Result<()> {
+        let pending_admin = self.stake_manager.pending_admin;
+        require_keys_neq!(
+            pending_admin,
+            Pubkey::default(),
+            Errors::PendingAdminNotMatch
+        );
+
+        self.stake_manager.pending_admin = Pubkey::default();
+
+        emit!(EventCancelAdminTransfer {
+            stake_manager: self.stake_manager.key(),
+            admin: self.admin.key(),
+            pending_admin,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct RenounceAdmin<'info> {
     pub admin: Signer<'info>,
 
     #[account(
@@ -53,35 +123,556 @@ pub struct ConfigStakeManager<'info> {
     pub stake_manager: Box<Account<'info, StakeManager>>,
 }
 
+#[event]
+pub struct EventRenounceAdmin {
+    pub stake_manager: Pubkey,
+    pub admin: Pubkey,
+}
+
+impl<'info> RenounceAdmin<'info> {
+    pub fn process(&mut self) -> Result<()> {
+        // a pause left in place could never be lifted again, nor a migration finished
+        require_eq!(self.stake_manager.paused, 0, Errors::Paused);
+        require!(
+            !self.stake_manager.is_migrating(),
+            Errors::StakingPoolMigrating
+        );
+
+        // platform fees would go to the default address once there is no admin to fall back to
+        self.stake_manager.fee_recipient = self.stake_manager.fee_recipient_or_admin();
+        self.stake_manager.renounced = true;
+        self.stake_manager.admin = Pubkey::default();
+        self.stake_manager.pending_admin = Pubkey::default();
+        for role in [
+            Role::Operator,
+            Role::FeeManager,
+            Role::Pauser,
+            Role::MetadataAuthority,
+        ] {
+            self.stake_manager.set_pending_role(role, Pubkey::default());
+            self.stake_manager.accept_role(role);
+        }
+
+        self.stake_manager.pending_config = PendingConfig::default();
+
+        emit!(EventRenounceAdmin {
+            stake_manager: self.stake_manager.key(),
+            admin: self.admin.key(),
+        });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct SetFeeRecipient<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = admin @ Errors::AdminNotMatch
+    )]
+    pub stake_manager: Box<Account<'info, StakeManager>>,
+}
+
+#[event]
+pub struct EventSetFeeRecipient {
+    pub stake_manager: Pubkey,
+    pub admin: Pubkey,
+    pub old_fee_recipient: Pubkey,
+    pub new_fee_recipient: Pubkey,
+}
+
+impl<'info> SetFeeRecipient<'info> {
+    pub fn process(&mut self, new_fee_recipient: Pubkey) -> Result<()> {
+        require_keys_neq!(new_fee_recipient, Pubkey::default(), Errors::ParamsNotMatch);
+
+        let old_fee_recipient = self.stake_manager.fee_recipient;
+        self.stake_manager.fee_recipient = new_fee_recipient;
+
+        emit!(EventSetFeeRecipient {
+            stake_manager: self.stake_manager.key(),
+            admin: self.admin.key(),
+            old_fee_recipient,
+            new_fee_recipient,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct SetFeeSplits<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub stake_manager: Box<Account<'info, StakeManager>>,
+}
+
+#[event]
+pub struct EventSetFeeSplits {
+    pub stake_manager: Pubkey,
+    pub authority: Pubkey,
+    pub old_fee_splits: Vec<FeeSplit>,
+    pub new_fee_splits: Vec<FeeSplit>,
+}
+
+impl<'info> SetFeeSplits<'info> {
+    pub fn process(
+        &mut self,
+        fee_splits: Vec<FeeSplit>,
+        fee_split_recipients: &'info [AccountInfo<'info>],
+    ) -> Result<()> {
+        self.stake_manager
+            .check_role(Role::FeeManager, &self.authority.key())?;
+
+        require_gte!(
+            helper::FEE_SPLITS_LEN_LIMIT as usize,
+            fee_splits.len(),
+            Errors::FeeSplitsNotMatch
+        );
+        require_eq!(
+            fee_split_recipients.len(),
+            fee_splits.len(),
+            Errors::FeeSplitsNotMatch
+        );
+
+        let mut total_weight: u64 = 0;
+        for (fee_split, recipient) in fee_splits.iter().zip(fee_split_recipients.iter()) {
+            require_keys_eq!(
+                recipient.key(),
+                fee_split.recipient,
+                Errors::FeeSplitsNotMatch
+            );
+
+            let recipient_token_account = InterfaceAccount::<TokenAccount>::try_from(recipient)?;
+            require_keys_eq!(
+                recipient_token_account.mint,
+                self.stake_manager.lsd_token_mint,
+                Errors::LsdTokenMintAccountNotMatch
+            );
+
+            total_weight += fee_split.weight;
+        }
+        require_gte!(helper::CAL_BASE, total_weight, Errors::FeeSplitsNotMatch);
+
+        let old_fee_splits = self.stake_manager.fee_splits.clone();
+        self.stake_manager.fee_splits = fee_splits.clone();
+
+        emit!(EventSetFeeSplits {
+            stake_manager: self.stake_manager.key(),
+            authority: self.authority.key(),
+            old_fee_splits,
+            new_fee_splits: fee_splits,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct SetEraSeconds<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = admin @ Errors::AdminNotMatch
+    )]
+    pub stake_manager: Box<Account<'info, StakeManager>>,
+
+    #[account(
+        address = stake_manager.staking_pool @Errors::SpStakePoolNotMatch,
+    )]
+    pub staking_pool: Box<Account<'info, staking_program::StakingPool>>,
+}
+
+#[event]
+pub struct EventSetEraSeconds {
+    pub stake_manager: Pubkey,
+    pub admin: Pubkey,
+    pub era: u64,
+    pub old_era_seconds: i64,
+    pub new_era_seconds: i64,
+    pub old_era_offset: i64,
+    pub new_era_offset: i64,
+    pub old_unbonding_duration: u64,
+    pub new_unbonding_duration: u64,
+}
+
+impl<'info> SetEraSeconds<'info> {
+    pub fn process(&mut self, new_era_seconds: i64) -> Result<()> {
+        require_gt!(new_era_seconds, 0, Errors::ParamsNotMatch);
+        require!(
+            self.stake_manager.era_status == EraStatus::ActiveUpdated,
+            Errors::EraStatusNotMatch
+        );
+        require!(
+            !self.stake_manager.is_migrating(),
+            Errors::StakingPoolMigrating
+        );
+        // unstake accounts mature after a number of eras, shorter eras would let them withdraw
+        // before their unbond from the staking pool landed. Keypair unstake accounts and their
+        // unbonds are counted in neither pending_unstake_amount nor unbonding.
+        if new_era_seconds < self.stake_manager.era_seconds {
+            require!(
+                self.stake_manager.version > 0 && !self.stake_manager.legacy_unstake_accounts,
+                Errors::LegacyUnstakeAccounts
+            );
+            require!(
+                self.stake_manager.pending_unstake_amount == 0 && self.stake_manager.unbonding == 0,
+                Errors::UnstakesOutstanding
+            );
+        }
+
+        let timestamp = Clock::get()?.unix_timestamp;
+        let current_era = self.stake_manager.calc_current_era(timestamp)?;
+
+        // keep the current era number across the switch, only the following eras change length
+        let new_era_offset = current_era as i64 - timestamp / new_era_seconds;
+        let new_unbonding_duration =
+            self.staking_pool.unbonding_seconds / new_era_seconds as u64 + 1;
+
+        let old_era_seconds = self.stake_manager.era_seconds;
+        let old_era_offset = self.stake_manager.era_offset;
+        let old_unbonding_duration = self.stake_manager.unbonding_duration;
+
+        self.stake_manager.era_seconds = new_era_seconds;
+        self.stake_manager.era_offset = new_era_offset;
+        self.stake_manager.unbonding_duration = new_unbonding_duration;
+
+        emit!(EventSetEraSeconds {
+            stake_manager: self.stake_manager.key(),
+            admin: self.admin.key(),
+            era: current_era,
+            old_era_seconds,
+            new_era_seconds,
+            old_era_offset,
+            new_era_offset,
+            old_unbonding_duration,
+            new_unbonding_duration,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct StartWindDown<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = admin @ Errors::AdminNotMatch
+    )]
+    pub stake_manager: Box<Account<'info, StakeManager>>,
+}
+
+#[event]
+pub struct EventStartWindDown {
+    pub stake_manager: Pubkey,
+    pub admin: Pubkey,
+    pub era: u64,
+}
+
+impl<'info> StartWindDown<'info> {
+    pub fn process(&mut self) -> Result<()> {
+        require!(!self.stake_manager.wind_down, Errors::WindDown);
+        require!(
+            !self.stake_manager.is_migrating(),
+            Errors::StakingPoolMigrating
+        );
+
+        // irreversible, stakes are blocked and the following eras unbond everything
+        self.stake_manager.wind_down = true;
+
+        emit!(EventStartWindDown {
+            stake_manager: self.stake_manager.key(),
+            admin: self.admin.key(),
+            era: self.stake_manager.latest_era,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct ClearLegacyUnstakeAccounts<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = admin @ Errors::AdminNotMatch
+    )]
+    pub stake_manager: Box<Account<'info, StakeManager>>,
+}
+
+#[event]
+pub struct EventClearLegacyUnstakeAccounts {
+    pub stake_manager: Pubkey,
+    pub admin: Pubkey,
+    pub era: u64,
+}
+
+impl<'info> ClearLegacyUnstakeAccounts<'info> {
+    pub fn process(&mut self) -> Result<()> {
+        require!(
+            self.stake_manager.legacy_unstake_accounts,
+            Errors::NoLegacyUnstakeAccounts
+        );
+        // only on the way to close_stake_manager, withdraw_legacy is rejected from now on and
+        // keypair unstake accounts not withdrawn yet are left to the close like any dust
+        require!(self.stake_manager.wind_down, Errors::NotWindDown);
+        require_gte!(
+            self.stake_manager.latest_era,
+            self.stake_manager.legacy_withdrawable_era,
+            Errors::LegacyUnstakeAccounts
+        );
+
+        self.stake_manager.legacy_unstake_accounts = false;
+
+        emit!(EventClearLegacyUnstakeAccounts {
+            stake_manager: self.stake_manager.key(),
+            admin: self.admin.key(),
+            era: self.stake_manager.latest_era,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct TransferStakeManagerRole<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = admin @ Errors::AdminNotMatch
+    )]
+    pub stake_manager: Box<Account<'info, StakeManager>>,
+}
+
+#[event]
+pub struct EventTransferStakeManagerRole {
+    pub stake_manager: Pubkey,
+    pub admin: Pubkey,
+    pub role: Role,
+    pub old_pending_holder: Pubkey,
+    pub new_pending_holder: Pubkey,
+}
+
+impl<'info> TransferStakeManagerRole<'info> {
+    pub fn process(&mut self, role: Role, new_holder: Pubkey) -> Result<()> {
+        let old_pending_holder = self.stake_manager.pending_role(role);
+        self.stake_manager.set_pending_role(role, new_holder);
+
+        emit!(EventTransferStakeManagerRole {
+            stake_manager: self.stake_manager.key(),
+            admin: self.admin.key(),
+            role,
+            old_pending_holder,
+            new_pending_holder: new_holder,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct AcceptStakeManagerRole<'info> {
+    pub pending_holder: Signer<'info>,
+
+    #[account(mut)]
+    pub stake_manager: Box<Account<'info, StakeManager>>,
+}
+
+#[event]
+pub struct EventAcceptStakeManagerRole {
+    pub stake_manager: Pubkey,
+    pub pending_holder: Pubkey,
+    pub role: Role,
+    pub old_holder: Pubkey,
+    pub new_holder: Pubkey,
+}
+
+impl<'info> AcceptStakeManagerRole<'info> {
+    pub fn process(&mut self, role: Role) -> Result<()> {
+        require_keys_eq!(
+            self.stake_manager.pending_role(role),
+            self.pending_holder.key(),
+            Errors::PendingRoleNotMatch
+        );
+
+        let old_holder = self.stake_manager.role(role);
+        self.stake_manager.accept_role(role);
+
+        emit!(EventAcceptStakeManagerRole {
+            stake_manager: self.stake_manager.key(),
+            pending_holder: self.pending_holder.key(),
+            role,
+            old_holder,
+            new_holder: self.stake_manager.role(role),
+        });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub stake_manager: Box<Account<'info, StakeManager>>,
+}
+
+#[event]
+pub struct EventSetPaused {
+    pub stake_manager: Pubkey,
+    pub authority: Pubkey,
+    pub old_paused: u32,
+    pub new_paused: u32,
+}
+
+impl<'info> SetPaused<'info> {
+    pub fn process(&mut self, paused: u32) -> Result<()> {
+        self.stake_manager
+            .check_role(Role::Pauser, &self.authority.key())?;
+
+        let old_paused = self.stake_manager.paused;
+        self.stake_manager.paused = paused;
+
+        emit!(EventSetPaused {
+            stake_manager: self.stake_manager.key(),
+            authority: self.authority.key(),
+            old_paused,
+            new_paused: paused,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct ConfigStakeManager<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub stake_manager: Box<Account<'info, StakeManager>>,
+}
+
 #[derive(Clone, Copy, Debug, Default, PartialEq, AnchorSerialize, AnchorDeserialize)]
 pub struct ConfigStakeManagerParams {
     pub min_stake_amount: Option<u64>,
     pub platform_fee_commission: Option<u64>,
     pub rate_change_limit: Option<u64>,
+    pub instant_unstake_fee_commission: Option<u64>,
+    pub instant_unstake_fee_mode: Option<InstantUnstakeFeeMode>,
+    pub buffer_target_bps: Option<u64>,
+    pub config_delay: Option<i64>,
 }
 
-impl<'info> ConfigStakeManager<'info> {
-    pub fn process(&mut self, config_stake_manager_params: ConfigStakeManagerParams) -> Result<()> {
-        if let Some(min_stake_amount) = config_stake_manager_params.min_stake_amount {
-            self.stake_manager.min_stake_amount = min_stake_amount;
-            msg!("min_stake_amount: {}", min_stake_amount);
+impl ConfigStakeManagerParams {
+    pub fn check(&self, stake_manager: &StakeManager, authority: &Pubkey) -> Result<()> {
+        require!(!stake_manager.renounced, Errors::AdminRenounced);
+
+        if self.min_stake_amount.is_some() || self.rate_change_limit.is_some() {
+            stake_manager.check_role(Role::Operator, authority)?;
         }
 
-        if let Some(platform_fee_commission) = config_stake_manager_params.platform_fee_commission {
+        if let Some(platform_fee_commission) = self.platform_fee_commission {
+            stake_manager.check_role(Role::FeeManager, authority)?;
+
             require!(
                 platform_fee_commission < 1_000_000_000,
                 Errors::ParamsNotMatch
             );
+        }
+
+        if let Some(instant_unstake_fee_commission) = self.instant_unstake_fee_commission {
+            stake_manager.check_role(Role::FeeManager, authority)?;
+
+            require!(
+                instant_unstake_fee_commission < 1_000_000_000,
+                Errors::ParamsNotMatch
+            );
+        }
+
+        if self.instant_unstake_fee_mode.is_some() {
+            stake_manager.check_role(Role::FeeManager, authority)?;
+        }
 
-            self.stake_manager.platform_fee_commission = platform_fee_commission;
-            msg!("platform_fee_commission: {}", platform_fee_commission);
+        if let Some(buffer_target_bps) = self.buffer_target_bps {
+            stake_manager.check_role(Role::Operator, authority)?;
+
+            require_gte!(helper::BPS_BASE, buffer_target_bps, Errors::ParamsNotMatch);
+        }
+
+        if let Some(config_delay) = self.config_delay {
+            require_keys_eq!(*authority, stake_manager.admin, Errors::AdminNotMatch);
+
+            require_gte!(config_delay, 0, Errors::ParamsNotMatch);
         }
 
-        if let Some(rate_change_limit) = config_stake_manager_params.rate_change_limit {
-            self.stake_manager.rate_change_limit = rate_change_limit;
-            msg!("rate_change_limit: {}", rate_change_limit);
+        Ok(())
+    }
+
+    // returns the replaced values, set only for the fields these params change
+    pub fn apply(&self, stake_manager: &mut StakeManager) -> ConfigStakeManagerParams {
+        let mut old_params = ConfigStakeManagerParams::default();
+
+        if let Some(min_stake_amount) = self.min_stake_amount {
+            old_params.min_stake_amount = Some(stake_manager.min_stake_amount);
+            stake_manager.min_stake_amount = min_stake_amount;
         }
 
+        if let Some(platform_fee_commission) = self.platform_fee_commission {
+            old_params.platform_fee_commission = Some(stake_manager.platform_fee_commission);
+            stake_manager.platform_fee_commission = platform_fee_commission;
+        }
+
+        if let Some(rate_change_limit) = self.rate_change_limit {
+            old_params.rate_change_limit = Some(stake_manager.rate_change_limit);
+            stake_manager.rate_change_limit = rate_change_limit;
+        }
+
+        if let Some(instant_unstake_fee_commission) = self.instant_unstake_fee_commission {
+            old_params.instant_unstake_fee_commission =
+                Some(stake_manager.instant_unstake_fee_commission);
+            stake_manager.instant_unstake_fee_commission = instant_unstake_fee_commission;
+        }
+
+        if let Some(instant_unstake_fee_mode) = self.instant_unstake_fee_mode {
+            old_params.instant_unstake_fee_mode = Some(stake_manager.instant_unstake_fee_mode);
+            stake_manager.instant_unstake_fee_mode = instant_unstake_fee_mode;
+        }
+
+        if let Some(buffer_target_bps) = self.buffer_target_bps {
+            old_params.buffer_target_bps = Some(stake_manager.buffer_target_bps);
+            stake_manager.buffer_target_bps = buffer_target_bps;
+        }
+
+        if let Some(config_delay) = self.config_delay {
+            old_params.config_delay = Some(stake_manager.config_delay);
+            stake_manager.config_delay = config_delay;
+        }
+
+        old_params
+    }
+}
+
+#[event]
+pub struct EventConfigStakeManager {
+    pub stake_manager: Pubkey,
+    pub authority: Pubkey,
+    pub old_params: ConfigStakeManagerParams,
+    pub new_params: ConfigStakeManagerParams,
+}
+
+impl<'info> ConfigStakeManager<'info> {
+    pub fn process(&mut self, config_stake_manager_params: ConfigStakeManagerParams) -> Result<()> {
+        // once a delay is set, changes have to go through propose_config
+        require_eq!(self.stake_manager.config_delay, 0, Errors::ConfigTimelocked);
+
+        config_stake_manager_params.check(&self.stake_manager, &self.authority.key())?;
+        let old_params = config_stake_manager_params.apply(&mut self.stake_manager);
+
+        emit!(EventConfigStakeManager {
+            stake_manager: self.stake_manager.key(),
+            authority: self.authority.key(),
+            old_params,
+            new_params: config_stake_manager_params,
+        });
         Ok(())
     }
 }
@@ -0,0 +1,144 @@
+use crate::{helper, EraStatus, Errors, StakeManager};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{
+    close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
+    TransferChecked,
+};
+use staking_program;
+
+#[derive(Accounts)]
+pub struct CloseStakeManager<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        close = admin,
+        has_one = admin @ Errors::AdminNotMatch
+    )]
+    pub stake_manager: Box<Account<'info, StakeManager>>,
+
+    #[account(
+        address = stake_manager.lsd_token_mint @Errors::LsdTokenMintAccountNotMatch
+    )]
+    pub lsd_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        address = stake_manager.staking_token_mint @Errors::StakingTokenMintAccountNotMatch
+    )]
+    pub staking_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        associated_token::mint = staking_token_mint,
+        associated_token::authority = admin,
+        associated_token::token_program = token_program,
+    )]
+    pub admin_staking_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = staking_token_mint,
+        associated_token::authority = stake_manager,
+        associated_token::token_program = token_program,
+    )]
+    pub stake_manager_staking_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub staking_stake_account: Box<Account<'info, staking_program::StakeAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct EventCloseStakeManager {
+    pub stake_manager: Pubkey,
+    pub admin: Pubkey,
+    pub era: u64,
+    pub dust_amount: u64,
+}
+
+impl<'info> CloseStakeManager<'info> {
+    pub fn process(&mut self) -> Result<()> {
+        require!(self.stake_manager.wind_down, Errors::NotWindDown);
+        require!(
+            self.stake_manager.era_status == EraStatus::ActiveUpdated,
+            Errors::EraStatusNotMatch
+        );
+        require!(
+            self.staking_stake_account.to_account_info().owner.key()
+                == self.stake_manager.staking_program
+                && self.staking_stake_account.user.key() == self.stake_manager.key()
+                && self.staking_stake_account.staking_pool.key() == self.stake_manager.staking_pool,
+            Errors::SpStakeAccountNotMatch
+        );
+
+        // keypair unstake accounts can't be counted, their staking tokens may still be in the pool
+        require!(
+            self.stake_manager.version > 0 && !self.stake_manager.legacy_unstake_accounts,
+            Errors::LegacyUnstakeAccounts
+        );
+        require_eq!(self.lsd_token_mint.supply, 0, Errors::StakeManagerNotEmpty);
+        require_eq!(
+            self.stake_manager.pending_unstake_amount,
+            0,
+            Errors::StakeManagerNotEmpty
+        );
+        // everything is unbonded and withdrawn, so what is left in the pool is rounding dust
+        require!(
+            self.staking_stake_account.amount == 0
+                && self.stake_manager.unbonding == 0
+                && self.stake_manager.pending_unbond == 0
+                && self.stake_manager.pending_rebond == 0
+                && self.stake_manager.buffer_unbonding == 0,
+            Errors::StakeManagerNotEmpty
+        );
+
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            helper::STAKE_MANAGER_SEED,
+            &self.stake_manager.creator.to_bytes(),
+            &[self.stake_manager.index],
+            &[self.stake_manager.pool_seed_bump],
+        ]];
+
+        // the token account must be empty to close
+        let dust_amount = self.stake_manager_staking_token_account.amount;
+        if dust_amount > 0 {
+            transfer_checked(
+                CpiContext::new_with_signer(
+                    self.token_program.to_account_info(),
+                    TransferChecked {
+                        from: self.stake_manager_staking_token_account.to_account_info(),
+                        mint: self.staking_token_mint.to_account_info(),
+                        to: self.admin_staking_token_account.to_account_info(),
+                        authority: self.stake_manager.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                dust_amount,
+                self.staking_token_mint.decimals,
+            )?;
+        }
+
+        close_account(CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            CloseAccount {
+                account: self.stake_manager_staking_token_account.to_account_info(),
+                destination: self.admin.to_account_info(),
+                authority: self.stake_manager.to_account_info(),
+            },
+            signer_seeds,
+        ))?;
+
+        emit!(EventCloseStakeManager {
+            stake_manager: self.stake_manager.key(),
+            admin: self.admin.key(),
+            era: self.stake_manager.latest_era,
+            dust_amount,
+        });
+        Ok(())
+    }
+}
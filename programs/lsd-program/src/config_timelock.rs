@@ -0,0 +1,129 @@
+use crate::{ConfigStakeManagerParams, Errors, PendingConfig, StakeManager};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ProposeConfig<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub stake_manager: Box<Account<'info, StakeManager>>,
+}
+
+#[event]
+pub struct EventProposeConfig {
+    pub stake_manager: Pubkey,
+    pub proposer: Pubkey,
+    pub params: ConfigStakeManagerParams,
+    pub effective_at: i64,
+}
+
+impl<'info> ProposeConfig<'info> {
+    pub fn process(&mut self, config_stake_manager_params: ConfigStakeManagerParams) -> Result<()> {
+        require!(
+            !self.stake_manager.pending_config.is_pending(),
+            Errors::PendingConfigExists
+        );
+
+        config_stake_manager_params.check(&self.stake_manager, &self.authority.key())?;
+
+        let timestamp = Clock::get()?.unix_timestamp;
+        let effective_at = timestamp + self.stake_manager.config_delay;
+
+        self.stake_manager.pending_config = PendingConfig::new(
+            &config_stake_manager_params,
+            self.authority.key(),
+            effective_at,
+        );
+
+        emit!(EventProposeConfig {
+            stake_manager: self.stake_manager.key(),
+            proposer: self.authority.key(),
+            params: config_stake_manager_params,
+            effective_at,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct ExecuteConfig<'info> {
+    pub executor: Signer<'info>,
+
+    #[account(mut)]
+    pub stake_manager: Box<Account<'info, StakeManager>>,
+}
+
+#[event]
+pub struct EventExecuteConfig {
+    pub stake_manager: Pubkey,
+    pub executor: Pubkey,
+    pub old_params: ConfigStakeManagerParams,
+    pub params: ConfigStakeManagerParams,
+}
+
+impl<'info> ExecuteConfig<'info> {
+    pub fn process(&mut self) -> Result<()> {
+        let pending_config = self.stake_manager.pending_config;
+        require!(pending_config.is_pending(), Errors::PendingConfigNotExist);
+        let config_stake_manager_params = pending_config.params();
+
+        let timestamp = Clock::get()?.unix_timestamp;
+        require_gte!(
+            timestamp,
+            pending_config.effective_at,
+            Errors::PendingConfigNotEffective
+        );
+        // the proposer may have lost the roles the params need since proposing
+        config_stake_manager_params.check(&self.stake_manager, &pending_config.proposer)?;
+
+        let old_params = config_stake_manager_params.apply(&mut self.stake_manager);
+
+        self.stake_manager.pending_config = PendingConfig::default();
+
+        emit!(EventExecuteConfig {
+            stake_manager: self.stake_manager.key(),
+            executor: self.executor.key(),
+            old_params,
+            params: config_stake_manager_params,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct CancelConfig<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub stake_manager: Box<Account<'info, StakeManager>>,
+}
+
+#[event]
+pub struct EventCancelConfig {
+    pub stake_manager: Pubkey,
+    pub authority: Pubkey,
+    pub params: ConfigStakeManagerParams,
+}
+
+impl<'info> CancelConfig<'info> {
+    pub fn process(&mut self) -> Result<()> {
+        let pending_config = self.stake_manager.pending_config;
+        require!(pending_config.is_pending(), Errors::PendingConfigNotExist);
+        let config_stake_manager_params = pending_config.params();
+
+        require!(
+            self.authority.key() == self.stake_manager.admin
+                || self.authority.key() == pending_config.proposer,
+            Errors::AdminNotMatch
+        );
+
+        self.stake_manager.pending_config = PendingConfig::default();
+
+        emit!(EventCancelConfig {
+            stake_manager: self.stake_manager.key(),
+            authority: self.authority.key(),
+            params: config_stake_manager_params,
+        });
+        Ok(())
+    }
+}
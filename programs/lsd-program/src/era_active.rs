@@ -1,4 +1,4 @@
-use crate::{helper, EraRate, EraStatus, Errors, StakeManager};
+use crate::{helper, EraRate, EraSnapshot, EraStatus, Errors, StakeManager};
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token_interface::{mint_to, Mint, MintTo, TokenAccount, TokenInterface};
@@ -10,14 +10,26 @@ pub struct EraActive<'info> {
 
     /// CHECK: stake_manager
     #[account(
-        mut,
-        address = stake_manager.admin @Errors::AdminNotMatch
+        address = stake_manager.fee_recipient_or_admin() @Errors::PlatformFeeRecipientNotMatch,
     )]
-    pub admin: AccountInfo<'info>,
+    pub fee_recipient: AccountInfo<'info>,
 
     #[account(mut)]
     pub stake_manager: Box<Account<'info, StakeManager>>,
 
+    #[account(
+        init,
+        space = 8 + std::mem::size_of::<EraSnapshot>(),
+        payer = rent_payer,
+        seeds = [
+            helper::ERA_SNAPSHOT_SEED,
+            &stake_manager.key().to_bytes(),
+            &stake_manager.latest_era.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub era_snapshot: Box<Account<'info, EraSnapshot>>,
+
     #[account(
         mut,
         address = stake_manager.lsd_token_mint @Errors::LsdTokenMintAccountNotMatch
@@ -38,10 +50,9 @@ pub struct EraActive<'info> {
     pub stake_manager_staking_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
-        init_if_needed,
-        payer = rent_payer,
+        mut,
         associated_token::mint = lsd_token_mint,
-        associated_token::authority = admin,
+        associated_token::authority = fee_recipient,
         associated_token::token_program = token_program,
     )]
     pub platform_fee_recipient: Box<InterfaceAccount<'info, TokenAccount>>,
@@ -58,6 +69,26 @@ pub struct EraActive<'info> {
     #[account(mut)]
     pub staking_stake_account: Box<Account<'info, staking_program::StakeAccount>>,
 
+    // the new staking pool position, required once the migration bonded into it
+    #[account(mut)]
+    pub migration_staking_stake_account: Option<Box<Account<'info, staking_program::StakeAccount>>>,
+
+    #[account(
+        mut,
+        address = stake_manager.migration_staking_pool @Errors::SpStakePoolNotMatch,
+    )]
+    pub migration_staking_pool: Option<Box<Account<'info, staking_program::StakingPool>>>,
+
+    #[account(mut)]
+    pub migration_staking_pool_staking_token_account:
+        Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// CHECK: staking_program
+    #[account(
+        address = stake_manager.migration_staking_program @Errors::SpNotMatch
+    )]
+    pub migration_staking_program: Option<UncheckedAccount<'info>>,
+
     /// CHECK: staking_program
     #[account(
         address = stake_manager.staking_program @Errors::SpNotMatch
@@ -69,20 +100,41 @@ pub struct EraActive<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Clone, Debug, Default, AnchorSerialize, AnchorDeserialize)]
+pub struct FeeSplitAmount {
+    pub recipient: Pubkey,
+    pub amount: u64,
+}
+
 #[event]
 pub struct EventEraActive {
     pub era: u64,
     pub rate: u64,
     pub platform_fee: u64,
+    pub fee_split_amounts: Vec<FeeSplitAmount>,
+    pub stake_manager: Pubkey,
+    pub old_rate: u64,
+    pub old_active: u64,
+    pub new_active: u64,
+    pub reward: u64,
+    pub lsd_token_supply: u64,
 }
 
 impl<'info> EraActive<'info> {
-    pub fn process(&mut self) -> Result<()> {
+    pub fn process(&mut self, fee_split_recipients: &'info [AccountInfo<'info>]) -> Result<()> {
+        self.stake_manager
+            .check_not_paused(helper::PAUSE_ERA_ACTIVE)?;
+
         require!(
             self.stake_manager.era_status == EraStatus::Bonded
                 || self.stake_manager.era_status == EraStatus::Unbonded,
             Errors::EraStatusNotMatch
         );
+        require_eq!(
+            fee_split_recipients.len(),
+            self.stake_manager.fee_splits.len(),
+            Errors::FeeSplitsNotMatch
+        );
         require!(
             self.staking_stake_account.to_account_info().owner.key() == self.staking_program.key()
                 && self.staking_stake_account.user.key() == self.stake_manager.key()
@@ -117,9 +169,17 @@ impl<'info> EraActive<'info> {
             true,
         )?;
 
+        self.claim_migration_reward()?;
+        let migration_amount = self.calc_migration_amount()?;
+
         let total_bond_and_reward = self.staking_stake_account.amount
             + self.stake_manager.pending_bond
-            + self.stake_manager.era_bond;
+            + self.stake_manager.era_bond
+            + self.stake_manager.buffer
+            + self.stake_manager.wind_down_idle
+            + self.stake_manager.pending_rebond
+            + self.stake_manager.buffer_unbonding
+            + migration_amount;
         let total_unbond = self.stake_manager.era_unbond;
 
         require_gte!(total_bond_and_reward, total_unbond, Errors::CalculationFail);
@@ -133,24 +193,38 @@ impl<'info> EraActive<'info> {
         };
 
         let platform_fee = self.stake_manager.calc_platform_fee(reward)?;
+        let mut fee_split_amounts = vec![];
         if platform_fee > 0 {
-            mint_to(
-                CpiContext::new_with_signer(
-                    self.token_program.to_account_info(),
-                    MintTo {
-                        mint: self.lsd_token_mint.to_account_info(),
-                        to: self.platform_fee_recipient.to_account_info(),
-                        authority: self.stake_manager.to_account_info(),
-                    },
-                    &[&[
-                        helper::STAKE_MANAGER_SEED,
-                        &self.stake_manager.creator.to_bytes(),
-                        &[self.stake_manager.index],
-                        &[self.stake_manager.pool_seed_bump],
-                    ]],
-                ),
-                platform_fee,
-            )?;
+            let mut remainder = platform_fee;
+            let fee_splits = self.stake_manager.fee_splits.clone();
+            for (fee_split, recipient) in fee_splits.iter().zip(fee_split_recipients.iter()) {
+                require_keys_eq!(
+                    recipient.key(),
+                    fee_split.recipient,
+                    Errors::FeeSplitsNotMatch
+                );
+
+                // a closed or frozen recipient must not stop the era, its share goes to the remainder
+                let amount = if self.can_receive_platform_fee(recipient) {
+                    self.stake_manager
+                        .calc_fee_split_amount(platform_fee, fee_split.weight)?
+                } else {
+                    0
+                };
+                self.mint_platform_fee(recipient.clone(), amount)?;
+
+                remainder -= amount;
+                fee_split_amounts.push(FeeSplitAmount {
+                    recipient: recipient.key(),
+                    amount,
+                });
+            }
+
+            self.mint_platform_fee(self.platform_fee_recipient.to_account_info(), remainder)?;
+            fee_split_amounts.push(FeeSplitAmount {
+                recipient: self.platform_fee_recipient.key(),
+                amount: remainder,
+            });
 
             self.stake_manager.total_platform_fee += platform_fee;
             self.lsd_token_mint.reload()?;
@@ -163,16 +237,23 @@ impl<'info> EraActive<'info> {
             .stake_manager
             .calc_rate_change(self.stake_manager.rate, new_rate)?;
 
-        if self.stake_manager.rate_change_limit > 0 {
-            require_gte!(
-                self.stake_manager.rate_change_limit,
-                rate_change,
-                Errors::RateChangeOverLimit
-            );
+        // a catch up cycle settles the reward of every era it skipped
+        let mut rate_change_limit = self.stake_manager.rate_change_limit;
+        if self.stake_manager.latest_era
+            == self.stake_manager.catch_up_era + self.stake_manager.catch_up_shift + 1
+        {
+            rate_change_limit =
+                rate_change_limit.saturating_mul(self.stake_manager.catch_up_shift + 1);
+        }
+        if rate_change_limit > 0 {
+            require_gte!(rate_change_limit, rate_change, Errors::RateChangeOverLimit);
         }
+        let old_rate = self.stake_manager.rate;
+        let old_active = self.stake_manager.active;
         self.stake_manager.active = new_active;
         self.stake_manager.rate = new_rate;
 
+        // only the recent rates are kept here, the full history lives in the era snapshots
         let latest_era = self.stake_manager.latest_era;
         self.stake_manager.era_rates.push(EraRate {
             era: latest_era,
@@ -184,12 +265,149 @@ impl<'info> EraActive<'info> {
 
         self.stake_manager.era_status = EraStatus::ActiveUpdated;
 
+        self.era_snapshot.set_inner(EraSnapshot {
+            stake_manager: self.stake_manager.key(),
+            era: latest_era,
+            rate: new_rate,
+            active: new_active,
+            lsd_token_supply: self.lsd_token_mint.supply,
+            reward,
+            platform_fee,
+            bond_amount: self.stake_manager.bonded_amount,
+            unbond_amount: self.stake_manager.unbonded_amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
         emit!(EventEraActive {
             era: self.stake_manager.latest_era,
             rate: new_rate,
-            platform_fee: platform_fee
+            platform_fee: platform_fee,
+            fee_split_amounts,
+            stake_manager: self.stake_manager.key(),
+            old_rate,
+            old_active,
+            new_active,
+            reward,
+            lsd_token_supply: self.lsd_token_mint.supply,
         });
 
         Ok(())
     }
+
+    // rewards of the new staking pool position are restaked there like the old ones
+    fn claim_migration_reward(&mut self) -> Result<()> {
+        if !self.stake_manager.is_migrating() || !self.stake_manager.migration_bonded {
+            return Ok(());
+        }
+
+        let (
+            Some(migration_staking_stake_account),
+            Some(migration_staking_pool),
+            Some(migration_staking_pool_staking_token_account),
+            Some(migration_staking_program),
+        ) = (
+            self.migration_staking_stake_account.as_mut(),
+            self.migration_staking_pool.as_ref(),
+            self.migration_staking_pool_staking_token_account.as_ref(),
+            self.migration_staking_program.as_ref(),
+        )
+        else {
+            return err!(Errors::SpStakeAccountNotMatch);
+        };
+        require!(
+            migration_staking_stake_account
+                .to_account_info()
+                .owner
+                .key()
+                == self.stake_manager.migration_staking_program
+                && migration_staking_stake_account.user.key() == self.stake_manager.key()
+                && migration_staking_stake_account.staking_pool.key()
+                    == self.stake_manager.migration_staking_pool,
+            Errors::SpStakeAccountNotMatch
+        );
+
+        let cpi_accounts = staking_program::cpi::accounts::Claim {
+            user: self.stake_manager.to_account_info(),
+            rent_payer: self.rent_payer.to_account_info(),
+            staking_pool: migration_staking_pool.to_account_info(),
+            token_mint: self.staking_token_mint.to_account_info(),
+            user_token_account: self.stake_manager_staking_token_account.to_account_info(),
+            pool_token_account: migration_staking_pool_staking_token_account.to_account_info(),
+            stake_account: migration_staking_stake_account.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+            associated_token_program: self.associated_token_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+        };
+
+        staking_program::cpi::claim(
+            CpiContext::new_with_signer(
+                migration_staking_program.to_account_info(),
+                cpi_accounts,
+                &[&[
+                    helper::STAKE_MANAGER_SEED,
+                    &self.stake_manager.creator.to_bytes(),
+                    &[self.stake_manager.index],
+                    &[self.stake_manager.pool_seed_bump],
+                ]],
+            ),
+            true,
+        )?;
+
+        migration_staking_stake_account.reload()
+    }
+
+    // the new staking pool position plus what is unbonded from the old one and not owed to unstakes
+    fn calc_migration_amount(&self) -> Result<u64> {
+        if !self.stake_manager.is_migrating() {
+            return Ok(0);
+        }
+
+        let mut migration_amount =
+            self.stake_manager.migration_unbonding - self.stake_manager.migration_reserved;
+        if self.stake_manager.migration_bonded {
+            let migration_staking_stake_account = self
+                .migration_staking_stake_account
+                .as_ref()
+                .ok_or(error!(Errors::SpStakeAccountNotMatch))?;
+            migration_amount += migration_staking_stake_account.amount;
+        }
+
+        Ok(migration_amount)
+    }
+
+    fn can_receive_platform_fee(&self, recipient: &AccountInfo<'info>) -> bool {
+        if recipient.owner != &self.token_program.key() {
+            return false;
+        }
+        let Ok(data) = recipient.try_borrow_data() else {
+            return false;
+        };
+        TokenAccount::try_deserialize(&mut &data[..]).is_ok_and(|token_account| {
+            token_account.mint == self.lsd_token_mint.key() && !token_account.is_frozen()
+        })
+    }
+
+    fn mint_platform_fee(&self, to: AccountInfo<'info>, amount: u64) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+
+        mint_to(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                MintTo {
+                    mint: self.lsd_token_mint.to_account_info(),
+                    to,
+                    authority: self.stake_manager.to_account_info(),
+                },
+                &[&[
+                    helper::STAKE_MANAGER_SEED,
+                    &self.stake_manager.creator.to_bytes(),
+                    &[self.stake_manager.index],
+                    &[self.stake_manager.pool_seed_bump],
+                ]],
+            ),
+            amount,
+        )
+    }
 }
@@ -0,0 +1,331 @@
+use crate::{helper, EraStatus, Errors, StakeManager};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke;
+use anchor_lang::InstructionData;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::TokenInterface;
+
+// every step is a cpi into the era instruction itself, which validates its own accounts
+#[derive(Accounts)]
+pub struct EraAdvance<'info> {
+    #[account(mut)]
+    pub fee_and_rent_payer: Signer<'info>,
+
+    // reloaded after every step, so the data written back on exit is the latest
+    #[account(mut)]
+    pub stake_manager: Box<Account<'info, StakeManager>>,
+
+    /// CHECK: era_active
+    pub fee_recipient: UncheckedAccount<'info>,
+
+    /// CHECK: era_active, derived from the era this call ends up in
+    #[account(mut)]
+    pub era_snapshot: UncheckedAccount<'info>,
+
+    /// CHECK: era_active
+    #[account(mut)]
+    pub lsd_token_mint: UncheckedAccount<'info>,
+
+    /// CHECK: era_bond, era_active
+    pub staking_token_mint: UncheckedAccount<'info>,
+
+    /// CHECK: era_bond, era_active
+    #[account(mut)]
+    pub stake_manager_staking_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: era_active
+    #[account(mut)]
+    pub platform_fee_recipient: UncheckedAccount<'info>,
+
+    /// CHECK: era_bond, era_unbond, era_active
+    #[account(mut)]
+    pub staking_pool: UncheckedAccount<'info>,
+
+    /// CHECK: era_bond, era_active
+    #[account(mut)]
+    pub staking_pool_staking_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: era_bond, era_unbond, era_active
+    #[account(mut)]
+    pub staking_stake_account: UncheckedAccount<'info>,
+
+    // only needed when the era unbonds
+    #[account(mut)]
+    pub staking_unstake_account: Option<Signer<'info>>,
+
+    /// CHECK: era_active
+    #[account(mut)]
+    pub migration_staking_stake_account: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: era_active
+    #[account(mut)]
+    pub migration_staking_pool: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: era_active
+    #[account(mut)]
+    pub migration_staking_pool_staking_token_account: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: era_active
+    pub migration_staking_program: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: era_bond, era_unbond, era_active
+    pub staking_program: UncheckedAccount<'info>,
+
+    pub lsd_program: Program<'info, crate::program::LsdProgram>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct EventEraAdvance {
+    pub stake_manager: Pubkey,
+    pub old_era: u64,
+    pub new_era: u64,
+    pub old_era_status: EraStatus,
+    pub new_era_status: EraStatus,
+    pub steps: u8,
+}
+
+impl<'info> EraAdvance<'info> {
+    pub fn process(&mut self, fee_split_recipients: &'info [AccountInfo<'info>]) -> Result<()> {
+        let old_era = self.stake_manager.latest_era;
+        let old_era_status = self.stake_manager.era_status.clone();
+        let timestamp = Clock::get()?.unix_timestamp;
+        let mut steps: u8 = 0;
+
+        // era_new may leave the era ActiveUpdated, then the next era can start right away
+        while self.stake_manager.era_status == EraStatus::ActiveUpdated
+            && !self.stake_manager.is_paused(helper::PAUSE_ERA_NEW)
+            && self.stake_manager.calc_current_era(timestamp)? > self.stake_manager.latest_era
+        {
+            self.era_new()?;
+            steps += 1;
+        }
+
+        if self.stake_manager.era_status == EraStatus::EraUpdated {
+            if self.can_bond() {
+                self.era_bond()?;
+                steps += 1;
+            } else if self.can_unbond() {
+                self.era_unbond()?;
+                steps += 1;
+            }
+        }
+
+        if (self.stake_manager.era_status == EraStatus::Bonded
+            || self.stake_manager.era_status == EraStatus::Unbonded)
+            && !self.stake_manager.is_paused(helper::PAUSE_ERA_ACTIVE)
+        {
+            self.era_active(fee_split_recipients)?;
+            steps += 1;
+        }
+
+        require_gt!(steps, 0, Errors::EraStatusNotMatch);
+
+        emit!(EventEraAdvance {
+            stake_manager: self.stake_manager.key(),
+            old_era,
+            new_era: self.stake_manager.latest_era,
+            old_era_status,
+            new_era_status: self.stake_manager.era_status.clone(),
+            steps,
+        });
+        Ok(())
+    }
+
+    // bonds go to another staking pool while migrating, use era_bond for those
+    fn can_bond(&self) -> bool {
+        !self.stake_manager.is_paused(helper::PAUSE_ERA_BOND)
+            && !self.stake_manager.wind_down
+            && !self.stake_manager.is_migrating()
+            && self.stake_manager.pending_bond > self.stake_manager.pending_unbond
+            && self.stake_manager.pending_bond - self.stake_manager.pending_unbond
+                >= self.stake_manager.staking_min_stake_amount
+    }
+
+    // unbonds come from another staking pool once the migration bonded, use era_unbond for those
+    fn can_unbond(&self) -> bool {
+        !self.stake_manager.is_paused(helper::PAUSE_ERA_UNBOND)
+            && (!self.stake_manager.is_migrating() || !self.stake_manager.migration_bonded)
+            && self.staking_unstake_account.is_some()
+            && (self.stake_manager.wind_down
+                || self.stake_manager.pending_bond < self.stake_manager.pending_unbond)
+    }
+
+    fn era_new(&mut self) -> Result<()> {
+        let accounts = crate::accounts::EraNew {
+            stake_manager: self.stake_manager.key(),
+        };
+
+        self.invoke_self(
+            accounts.to_account_metas(None),
+            crate::instruction::EraNew {}.data(),
+            &[self.stake_manager.to_account_info()],
+        )
+    }
+
+    fn era_bond(&mut self) -> Result<()> {
+        let accounts = crate::accounts::EraBond {
+            fee_and_rent_payer: self.fee_and_rent_payer.key(),
+            stake_manager: self.stake_manager.key(),
+            staking_token_mint: self.staking_token_mint.key(),
+            stake_manager_staking_token_account: self.stake_manager_staking_token_account.key(),
+            staking_pool: self.staking_pool.key(),
+            staking_pool_staking_token_account: self.staking_pool_staking_token_account.key(),
+            staking_stake_account: self.staking_stake_account.key(),
+            staking_program: self.staking_program.key(),
+            token_program: self.token_program.key(),
+            associated_token_program: self.associated_token_program.key(),
+            system_program: self.system_program.key(),
+        };
+
+        self.invoke_self(
+            accounts.to_account_metas(None),
+            crate::instruction::EraBond {}.data(),
+            &[
+                self.fee_and_rent_payer.to_account_info(),
+                self.stake_manager.to_account_info(),
+                self.staking_token_mint.to_account_info(),
+                self.stake_manager_staking_token_account.to_account_info(),
+                self.staking_pool.to_account_info(),
+                self.staking_pool_staking_token_account.to_account_info(),
+                self.staking_stake_account.to_account_info(),
+                self.staking_program.to_account_info(),
+                self.token_program.to_account_info(),
+                self.associated_token_program.to_account_info(),
+                self.system_program.to_account_info(),
+            ],
+        )
+    }
+
+    fn era_unbond(&mut self) -> Result<()> {
+        let staking_unstake_account = self
+            .staking_unstake_account
+            .as_ref()
+            .ok_or(error!(Errors::EraStatusNotMatch))?
+            .to_account_info();
+
+        let accounts = crate::accounts::EraUnbond {
+            fee_and_rent_payer: self.fee_and_rent_payer.key(),
+            stake_manager: self.stake_manager.key(),
+            staking_pool: self.staking_pool.key(),
+            staking_stake_account: self.staking_stake_account.key(),
+            staking_unstake_account: staking_unstake_account.key(),
+            staking_program: self.staking_program.key(),
+            system_program: self.system_program.key(),
+        };
+
+        self.invoke_self(
+            accounts.to_account_metas(None),
+            crate::instruction::EraUnbond {}.data(),
+            &[
+                self.fee_and_rent_payer.to_account_info(),
+                self.stake_manager.to_account_info(),
+                self.staking_pool.to_account_info(),
+                self.staking_stake_account.to_account_info(),
+                staking_unstake_account,
+                self.staking_program.to_account_info(),
+                self.system_program.to_account_info(),
+            ],
+        )
+    }
+
+    fn era_active(&mut self, fee_split_recipients: &'info [AccountInfo<'info>]) -> Result<()> {
+        let accounts = crate::accounts::EraActive {
+            rent_payer: self.fee_and_rent_payer.key(),
+            fee_recipient: self.fee_recipient.key(),
+            stake_manager: self.stake_manager.key(),
+            era_snapshot: self.era_snapshot.key(),
+            lsd_token_mint: self.lsd_token_mint.key(),
+            staking_token_mint: self.staking_token_mint.key(),
+            stake_manager_staking_token_account: self.stake_manager_staking_token_account.key(),
+            platform_fee_recipient: self.platform_fee_recipient.key(),
+            staking_pool: self.staking_pool.key(),
+            staking_pool_staking_token_account: self.staking_pool_staking_token_account.key(),
+            staking_stake_account: self.staking_stake_account.key(),
+            migration_staking_stake_account: self
+                .migration_staking_stake_account
+                .as_ref()
+                .map(|account| account.key()),
+            migration_staking_pool: self
+                .migration_staking_pool
+                .as_ref()
+                .map(|account| account.key()),
+            migration_staking_pool_staking_token_account: self
+                .migration_staking_pool_staking_token_account
+                .as_ref()
+                .map(|account| account.key()),
+            migration_staking_program: self
+                .migration_staking_program
+                .as_ref()
+                .map(|account| account.key()),
+            staking_program: self.staking_program.key(),
+            token_program: self.token_program.key(),
+            associated_token_program: self.associated_token_program.key(),
+            system_program: self.system_program.key(),
+        };
+
+        let mut account_metas = accounts.to_account_metas(None);
+        let mut account_infos = vec![
+            self.fee_and_rent_payer.to_account_info(),
+            self.fee_recipient.to_account_info(),
+            self.stake_manager.to_account_info(),
+            self.era_snapshot.to_account_info(),
+            self.lsd_token_mint.to_account_info(),
+            self.staking_token_mint.to_account_info(),
+            self.stake_manager_staking_token_account.to_account_info(),
+            self.platform_fee_recipient.to_account_info(),
+            self.staking_pool.to_account_info(),
+            self.staking_pool_staking_token_account.to_account_info(),
+            self.staking_stake_account.to_account_info(),
+            self.staking_program.to_account_info(),
+            self.token_program.to_account_info(),
+            self.associated_token_program.to_account_info(),
+            self.system_program.to_account_info(),
+        ];
+        for migration_account in [
+            &self.migration_staking_stake_account,
+            &self.migration_staking_pool,
+            &self.migration_staking_pool_staking_token_account,
+            &self.migration_staking_program,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            account_infos.push(migration_account.to_account_info());
+        }
+        for fee_split_recipient in fee_split_recipients.iter() {
+            account_metas.push(AccountMeta::new(fee_split_recipient.key(), false));
+            account_infos.push(fee_split_recipient.clone());
+        }
+
+        self.invoke_self(
+            account_metas,
+            crate::instruction::EraActive {}.data(),
+            &account_infos,
+        )
+    }
+
+    fn invoke_self(
+        &mut self,
+        account_metas: Vec<AccountMeta>,
+        data: Vec<u8>,
+        account_infos: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let mut account_infos = account_infos.to_vec();
+        account_infos.push(self.lsd_program.to_account_info());
+
+        invoke(
+            &Instruction {
+                program_id: crate::ID,
+                accounts: account_metas,
+                data,
+            },
+            &account_infos,
+        )?;
+
+        self.stake_manager.reload()
+    }
+}
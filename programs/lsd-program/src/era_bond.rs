@@ -27,7 +27,7 @@ pub struct EraBond<'info> {
 
     #[account(
         mut,
-        address = stake_manager.staking_pool @Errors::SpStakePoolNotMatch,
+        address = stake_manager.bond_staking_pool() @Errors::SpStakePoolNotMatch,
     )]
     pub staking_pool: Box<Account<'info, staking_program::StakingPool>>,
 
@@ -40,7 +40,7 @@ pub struct EraBond<'info> {
 
     /// CHECK: staking_program
     #[account(
-        address = stake_manager.staking_program @Errors::SpNotMatch
+        address = stake_manager.bond_staking_program() @Errors::SpNotMatch
     )]
     pub staking_program: AccountInfo<'info>,
 
@@ -52,10 +52,22 @@ pub struct EraBond<'info> {
 #[event]
 pub struct EventEraBond {
     pub era: u64,
+    pub stake_manager: Pubkey,
+    pub staking_program: Pubkey,
+    pub staking_pool: Pubkey,
+    pub bond_amount: u64,
+    pub old_pending_bond: u64,
+    pub new_pending_bond: u64,
+    pub old_pending_unbond: u64,
+    pub new_pending_unbond: u64,
 }
 
 impl<'info> EraBond<'info> {
     pub fn process(&mut self) -> Result<()> {
+        self.stake_manager
+            .check_not_paused(helper::PAUSE_ERA_BOND)?;
+        require!(!self.stake_manager.wind_down, Errors::WindDown);
+
         require!(
             self.stake_manager.era_status == EraStatus::EraUpdated,
             Errors::EraStatusNotMatch
@@ -65,6 +77,8 @@ impl<'info> EraBond<'info> {
             Errors::EraStatusNotMatch
         );
 
+        let old_pending_bond = self.stake_manager.pending_bond;
+        let old_pending_unbond = self.stake_manager.pending_unbond;
         let diff = self.stake_manager.pending_bond - self.stake_manager.pending_unbond;
         require!(
             diff >= self.stake_manager.staking_min_stake_amount,
@@ -98,12 +112,24 @@ impl<'info> EraBond<'info> {
             diff,
         )?;
 
+        if self.stake_manager.is_migrating() {
+            self.stake_manager.migration_bonded = true;
+        }
+        self.stake_manager.bonded_amount = diff;
         self.stake_manager.pending_bond = 0;
         self.stake_manager.pending_unbond = 0;
         self.stake_manager.era_status = EraStatus::Bonded;
 
         emit!(EventEraBond {
             era: self.stake_manager.latest_era,
+            stake_manager: self.stake_manager.key(),
+            staking_program: self.staking_program.key(),
+            staking_pool: self.staking_pool.key(),
+            bond_amount: diff,
+            old_pending_bond,
+            new_pending_bond: self.stake_manager.pending_bond,
+            old_pending_unbond,
+            new_pending_unbond: self.stake_manager.pending_unbond,
         });
 
         Ok(())
@@ -0,0 +1,65 @@
+use crate::{helper, start_new_era, EraStatus, Errors, StakeManager};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct EraCatchUp<'info> {
+    #[account(mut)]
+    pub stake_manager: Box<Account<'info, StakeManager>>,
+}
+
+#[event]
+pub struct EventEraCatchUp {
+    pub stake_manager: Pubkey,
+    pub old_era: u64,
+    pub new_era: u64,
+    // eras old_era + 1 ..= old_era + catch_up_shift are skipped and get no EraSnapshot
+    pub catch_up_shift: u64,
+    pub catch_up_unbonding_duration: u64,
+}
+
+impl<'info> EraCatchUp<'info> {
+    pub fn process(&mut self) -> Result<()> {
+        self.stake_manager.check_not_paused(helper::PAUSE_ERA_NEW)?;
+
+        require!(
+            self.stake_manager.era_status == EraStatus::ActiveUpdated,
+            Errors::EraStatusNotMatch
+        );
+
+        let timestamp = Clock::get().unwrap().unix_timestamp;
+        let old_era = self.stake_manager.latest_era;
+        let new_era = self.stake_manager.calc_current_era(timestamp)?;
+
+        require_gt!(new_era, old_era, Errors::EraIsLatest);
+
+        // only one shift is tracked, unstakes of the previous catch up must be withdrawable first.
+        // set_era_seconds or sync_staking_pool may have changed unbonding_duration since then.
+        if self.stake_manager.catch_up_shift > 0 {
+            require_gte!(
+                old_era,
+                self.stake_manager.catch_up_era
+                    + self.stake_manager.catch_up_shift
+                    + self.stake_manager.catch_up_unbonding_duration,
+                Errors::EraCatchUpPending
+            );
+        }
+
+        // unstakes made during old_era are unbonded by this cycle instead of the one of old_era + 1
+        let catch_up_shift = new_era - old_era - 1;
+        self.stake_manager.catch_up_era = old_era;
+        self.stake_manager.catch_up_shift = catch_up_shift;
+        self.stake_manager.catch_up_unbonding_duration = self.stake_manager.unbonding_duration;
+
+        start_new_era(&mut self.stake_manager, new_era)?;
+
+        emit!(EventEraCatchUp {
+            stake_manager: self.stake_manager.key(),
+            old_era,
+            new_era,
+            catch_up_shift,
+            catch_up_unbonding_duration: self.stake_manager.catch_up_unbonding_duration,
+        });
+
+        Ok(())
+    }
+}
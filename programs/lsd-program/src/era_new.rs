@@ -10,10 +10,18 @@ pub struct EraNew<'info> {
 #[event]
 pub struct EventEraNew {
     pub new_era: u64,
+    pub stake_manager: Pubkey,
+    pub era_status: EraStatus,
+    pub old_pending_bond: u64,
+    pub new_pending_bond: u64,
+    pub old_pending_unbond: u64,
+    pub new_pending_unbond: u64,
 }
 
 impl<'info> EraNew<'info> {
     pub fn process(&mut self) -> Result<()> {
+        self.stake_manager.check_not_paused(helper::PAUSE_ERA_NEW)?;
+
         require!(
             self.stake_manager.era_status == EraStatus::ActiveUpdated,
             Errors::EraStatusNotMatch
@@ -25,34 +33,79 @@ impl<'info> EraNew<'info> {
 
         require_gte!(current_era, new_era, Errors::EraIsLatest);
 
-        self.stake_manager.pending_unbond += self.stake_manager.era_unbond;
-        self.stake_manager.pending_bond += self.stake_manager.era_bond;
-
-        self.stake_manager.era_status = EraStatus::EraUpdated;
-        if self.stake_manager.pending_bond >= self.stake_manager.pending_unbond
-            && (self.stake_manager.pending_bond - self.stake_manager.pending_unbond)
-                < self.stake_manager.staking_min_stake_amount
-        {
-            self.stake_manager.pending_bond =
-                self.stake_manager.pending_bond - self.stake_manager.pending_unbond;
-            self.stake_manager.pending_unbond = 0;
-            self.stake_manager.era_status = EraStatus::Bonded;
-        }
-
-        if self.stake_manager.rate == helper::DEFAULT_RATE
-            && self.stake_manager.active < self.stake_manager.staking_min_stake_amount
-            && self.stake_manager.active + self.stake_manager.pending_unbond
-                == self.stake_manager.pending_bond
-        {
-            self.stake_manager.era_status = EraStatus::ActiveUpdated;
-        }
-
-        self.stake_manager.latest_era = new_era;
-        self.stake_manager.era_bond = 0;
-        self.stake_manager.era_unbond = 0;
-
-        emit!(EventEraNew { new_era });
-
-        Ok(())
+        start_new_era(&mut self.stake_manager, new_era)
+    }
+}
+
+// folds the finished era into pending and moves latest_era to new_era, shared with era_catch_up
+pub(crate) fn start_new_era(stake_manager: &mut Account<StakeManager>, new_era: u64) -> Result<()> {
+    let old_pending_bond = stake_manager.pending_bond;
+    let old_pending_unbond = stake_manager.pending_unbond;
+
+    stake_manager.release_landed_unbonds();
+
+    stake_manager.pending_unbond += stake_manager.era_unbond;
+    stake_manager.pending_bond += stake_manager.era_bond;
+
+    // keep buffer_target_bps of active unbonded. Net deposits refill it right away, under net
+    // unstakes the shortfall is unbonded along with them and lands in the buffer later.
+    let buffer_target = stake_manager.calc_buffer_target()?;
+    let shortfall =
+        buffer_target.saturating_sub(stake_manager.buffer + stake_manager.buffer_unbonding);
+    if stake_manager.buffer > buffer_target {
+        let release = stake_manager.buffer - buffer_target;
+        stake_manager.buffer = buffer_target;
+        stake_manager.pending_bond += release;
+    } else if stake_manager.pending_bond > stake_manager.pending_unbond {
+        let refill = shortfall.min(stake_manager.pending_bond - stake_manager.pending_unbond);
+        stake_manager.buffer += refill;
+        stake_manager.pending_bond -= refill;
+    } else if stake_manager.pending_unbond > stake_manager.pending_bond
+        && shortfall > 0
+        && !stake_manager.wind_down
+        && !stake_manager.is_migrating()
+    {
+        stake_manager.pending_unbond += shortfall;
+        stake_manager.buffer_unbonding += shortfall;
+        stake_manager.buffer_unbonding_era = new_era + stake_manager.unbonding_duration;
     }
+
+    // winding down always goes through era_unbond to pull everything out of the staking pool
+    stake_manager.era_status = EraStatus::EraUpdated;
+    if !stake_manager.wind_down
+        && stake_manager.pending_bond >= stake_manager.pending_unbond
+        && (stake_manager.pending_bond - stake_manager.pending_unbond)
+            < stake_manager.staking_min_stake_amount
+    {
+        stake_manager.pending_bond = stake_manager.pending_bond - stake_manager.pending_unbond;
+        stake_manager.pending_unbond = 0;
+        stake_manager.era_status = EraStatus::Bonded;
+    }
+
+    if !stake_manager.wind_down
+        && stake_manager.rate == helper::DEFAULT_RATE
+        && stake_manager.active < stake_manager.staking_min_stake_amount
+        && stake_manager.active + stake_manager.pending_unbond
+            == stake_manager.pending_bond + stake_manager.buffer
+    {
+        stake_manager.era_status = EraStatus::ActiveUpdated;
+    }
+
+    stake_manager.latest_era = new_era;
+    stake_manager.era_bond = 0;
+    stake_manager.era_unbond = 0;
+    stake_manager.bonded_amount = 0;
+    stake_manager.unbonded_amount = 0;
+
+    emit!(EventEraNew {
+        new_era,
+        stake_manager: stake_manager.key(),
+        era_status: stake_manager.era_status.clone(),
+        old_pending_bond,
+        new_pending_bond: stake_manager.pending_bond,
+        old_pending_unbond,
+        new_pending_unbond: stake_manager.pending_unbond,
+    });
+
+    Ok(())
 }
@@ -12,7 +12,7 @@ pub struct EraUnbond<'info> {
 
     #[account(
         mut,
-        address = stake_manager.staking_pool @Errors::SpStakePoolNotMatch,
+        address = stake_manager.unbond_staking_pool() @Errors::SpStakePoolNotMatch,
     )]
     pub staking_pool: Box<Account<'info, staking_program::StakingPool>>,
 
@@ -24,7 +24,7 @@ pub struct EraUnbond<'info> {
 
     /// CHECK: staking_program
     #[account(
-        address = stake_manager.staking_program @Errors::SpNotMatch
+        address = stake_manager.unbond_staking_program() @Errors::SpNotMatch
     )]
     pub staking_program: AccountInfo<'info>,
 
@@ -34,21 +34,121 @@ pub struct EraUnbond<'info> {
 #[event]
 pub struct EventEraUnbond {
     pub era: u64,
+    pub stake_manager: Pubkey,
+    pub staking_program: Pubkey,
+    pub staking_pool: Pubkey,
+    pub unbond_amount: u64,
+    pub old_pending_bond: u64,
+    pub new_pending_bond: u64,
+    pub old_pending_unbond: u64,
+    pub new_pending_unbond: u64,
 }
 
 impl<'info> EraUnbond<'info> {
     pub fn process(&mut self) -> Result<()> {
+        self.stake_manager
+            .check_not_paused(helper::PAUSE_ERA_UNBOND)?;
+
         require!(
             self.stake_manager.era_status == EraStatus::EraUpdated,
             Errors::EraStatusNotMatch
         );
+
+        let old_pending_bond = self.stake_manager.pending_bond;
+        let old_pending_unbond = self.stake_manager.pending_unbond;
+
+        let diff = if self.stake_manager.wind_down {
+            self.calc_wind_down_unbond()?
+        } else if self.stake_manager.is_migrating() {
+            self.calc_migration_unbond()?
+        } else {
+            require!(
+                self.stake_manager.pending_bond < self.stake_manager.pending_unbond,
+                Errors::EraStatusNotMatch
+            );
+
+            self.stake_manager.pending_unbond - self.stake_manager.pending_bond
+        };
+
+        if diff > 0 {
+            self.unstake(diff)?;
+        }
+        // migration unbonds are tracked in migration_unbonding
+        if !self.stake_manager.is_migrating() {
+            self.stake_manager.unbonding += diff;
+        }
+
+        self.stake_manager.unbonded_amount = diff;
+        self.stake_manager.pending_bond = 0;
+        self.stake_manager.pending_unbond = 0;
+        self.stake_manager.era_status = EraStatus::Unbonded;
+
+        emit!(EventEraUnbond {
+            era: self.stake_manager.latest_era,
+            stake_manager: self.stake_manager.key(),
+            staking_program: self.staking_program.key(),
+            staking_pool: self.staking_pool.key(),
+            unbond_amount: diff,
+            old_pending_bond,
+            new_pending_bond: self.stake_manager.pending_bond,
+            old_pending_unbond,
+            new_pending_unbond: self.stake_manager.pending_unbond,
+        });
+
+        Ok(())
+    }
+
+    // unbond the whole stake, whatever is not owed to this era's unstakes stays with the
+    // lsd holders as wind_down_idle
+    fn calc_wind_down_unbond(&mut self) -> Result<u64> {
+        require!(
+            self.staking_stake_account.to_account_info().owner.key() == self.staking_program.key()
+                && self.staking_stake_account.user.key() == self.stake_manager.key()
+                && self.staking_stake_account.staking_pool.key() == self.staking_pool.key(),
+            Errors::SpStakeAccountNotMatch
+        );
+
+        let stake_amount = self.staking_stake_account.amount;
+        let wind_down_idle =
+            self.stake_manager.wind_down_idle + stake_amount + self.stake_manager.pending_bond;
+        require_gte!(
+            wind_down_idle,
+            self.stake_manager.pending_unbond,
+            Errors::CalculationFail
+        );
+
+        self.stake_manager.wind_down_idle = wind_down_idle - self.stake_manager.pending_unbond;
+
+        Ok(stake_amount)
+    }
+
+    // unstakes are paid from what the migration already unbonded first. The rest is unbonded
+    // from staking_pool and withdrawn through migrate_withdraw until the migration bonded, then
+    // from migration_staking_pool and withdrawn by era_withdraw after the migration finished.
+    fn calc_migration_unbond(&mut self) -> Result<u64> {
         require!(
             self.stake_manager.pending_bond < self.stake_manager.pending_unbond,
             Errors::EraStatusNotMatch
         );
 
         let diff = self.stake_manager.pending_unbond - self.stake_manager.pending_bond;
+        let available =
+            self.stake_manager.migration_unbonding - self.stake_manager.migration_reserved;
+        let from_migration = diff.min(available);
+        let rest = diff - from_migration;
+
+        self.stake_manager.migration_reserved += from_migration;
+        if self.stake_manager.migration_bonded {
+            self.stake_manager.migration_pool_unbonding += rest;
+        } else {
+            self.stake_manager.migration_unbonding += rest;
+            self.stake_manager.migration_reserved += rest;
+        }
 
+        Ok(rest)
+    }
+
+    fn unstake(&self, amount: u64) -> Result<()> {
         let cpi_accounts = staking_program::cpi::accounts::Unstake {
             user: self.stake_manager.to_account_info(),
             rent_payer: self.fee_and_rent_payer.to_account_info(),
@@ -69,17 +169,7 @@ impl<'info> EraUnbond<'info> {
                     &[self.stake_manager.pool_seed_bump],
                 ]],
             ),
-            diff,
-        )?;
-
-        self.stake_manager.pending_bond = 0;
-        self.stake_manager.pending_unbond = 0;
-        self.stake_manager.era_status = EraStatus::Unbonded;
-
-        emit!(EventEraUnbond {
-            era: self.stake_manager.latest_era,
-        });
-
-        Ok(())
+            amount,
+        )
     }
 }
@@ -45,15 +45,38 @@ pub struct EraWithdraw<'info> {
 #[event]
 pub struct EventEraWithdraw {
     pub era: u64,
+    pub stake_manager: Pubkey,
+    pub staking_program: Pubkey,
+    pub staking_unstake_account: Pubkey,
+    pub withdraw_amount: u64,
+    pub old_unbonding: u64,
+    pub new_unbonding: u64,
+    pub old_pending_bond: u64,
+    pub new_pending_bond: u64,
+    pub pending_unbond: u64,
 }
 
 impl<'info> EraWithdraw<'info> {
     pub fn process(&mut self) -> Result<()> {
+        self.stake_manager
+            .check_not_paused(helper::PAUSE_ERA_WITHDRAW)?;
+
         let timestamp = Clock::get()?.unix_timestamp as u64;
         require!(
             self.staking_unstake_account.withdrawable_timestamp <= timestamp,
             Errors::UnstakeAccountNotWithdrawable
         );
+        // unstake accounts created by the migration go through migrate_withdraw
+        if self.stake_manager.is_migrating() {
+            require_gt!(
+                self.stake_manager
+                    .calc_migration_withdrawable_timestamp(self.staking_pool.unbonding_seconds),
+                self.staking_unstake_account.withdrawable_timestamp,
+                Errors::StakingPoolMigrating
+            );
+        }
+
+        let balance_before = self.stake_manager_staking_token_account.amount;
 
         let cpi_accounts = staking_program::cpi::accounts::Withdraw {
             user: self.stake_manager.to_account_info(),
@@ -79,8 +102,28 @@ impl<'info> EraWithdraw<'info> {
             ]],
         ))?;
 
+        self.stake_manager_staking_token_account.reload()?;
+        let withdraw_amount = self.stake_manager_staking_token_account.amount - balance_before;
+
+        let old_unbonding = self.stake_manager.unbonding;
+        let old_pending_bond = self.stake_manager.pending_bond;
+
+        // unbonds from before unbonding existed are not counted in it
+        self.stake_manager.unbonding = self.stake_manager.unbonding.saturating_sub(withdraw_amount);
+        self.stake_manager.release_landed_unbonds();
+
+        // withdrawing leaves pending_unbond untouched
         emit!(EventEraWithdraw {
             era: self.stake_manager.latest_era,
+            stake_manager: self.stake_manager.key(),
+            staking_program: self.staking_program.key(),
+            staking_unstake_account: self.staking_unstake_account.key(),
+            withdraw_amount,
+            old_unbonding,
+            new_unbonding: self.stake_manager.unbonding,
+            old_pending_bond,
+            new_pending_bond: self.stake_manager.pending_bond,
+            pending_unbond: self.stake_manager.pending_unbond,
         });
         Ok(())
     }
@@ -79,4 +79,85 @@ pub enum Errors {
 
     #[msg("Pending admin not match")]
     PendingAdminNotMatch,
+
+    #[msg("Buffer not enough")]
+    BufferNotEnough,
+
+    #[msg("Unstake account has ticket")]
+    UnstakeAccountHasTicket,
+
+    #[msg("Ticket mint not match")]
+    TicketMintNotMatch,
+
+    #[msg("Lsd token amount too low")]
+    LsdTokenAmountTooLow,
+
+    #[msg("Staking token amount too low")]
+    StakingTokenAmountTooLow,
+
+    #[msg("Era not match")]
+    EraNotMatch,
+
+    #[msg("Deadline exceeded")]
+    DeadlineExceeded,
+
+    #[msg("Paused")]
+    Paused,
+
+    #[msg("Role not match")]
+    RoleNotMatch,
+
+    #[msg("Pending role not match")]
+    PendingRoleNotMatch,
+
+    #[msg("Config timelocked")]
+    ConfigTimelocked,
+
+    #[msg("Pending config exists")]
+    PendingConfigExists,
+
+    #[msg("Pending config not exist")]
+    PendingConfigNotExist,
+
+    #[msg("Pending config not effective")]
+    PendingConfigNotEffective,
+
+    #[msg("Admin renounced")]
+    AdminRenounced,
+
+    #[msg("Fee splits not match")]
+    FeeSplitsNotMatch,
+
+    #[msg("Stake manager is winding down")]
+    WindDown,
+
+    #[msg("Stake manager is not winding down")]
+    NotWindDown,
+
+    #[msg("Stake manager not empty")]
+    StakeManagerNotEmpty,
+
+    #[msg("Staking pool migrating")]
+    StakingPoolMigrating,
+
+    #[msg("Staking pool not migrating")]
+    StakingPoolNotMigrating,
+
+    #[msg("Stake manager version is latest")]
+    StakeManagerVersionIsLatest,
+
+    #[msg("Invalid metadata account")]
+    InvalidMetadataAccount,
+
+    #[msg("Era catch up pending")]
+    EraCatchUpPending,
+
+    #[msg("Legacy unstake accounts")]
+    LegacyUnstakeAccounts,
+
+    #[msg("Unstakes outstanding")]
+    UnstakesOutstanding,
+
+    #[msg("No legacy unstake accounts")]
+    NoLegacyUnstakeAccounts,
 }
@@ -1,9 +1,46 @@
 pub const STAKE_MANAGER_SEED: &'static [u8] = b"stake_manager_seed";
 pub const TOKEN_MINT_SEED: &'static [u8] = b"token_mint_seed";
+pub const UNSTAKE_ACCOUNT_SEED: &'static [u8] = b"unstake_account_seed";
+pub const UNSTAKE_NONCE_SEED: &'static [u8] = b"unstake_nonce_seed";
+pub const TICKET_MINT_SEED: &'static [u8] = b"ticket_mint_seed";
+pub const ERA_SNAPSHOT_SEED: &'static [u8] = b"era_snapshot_seed";
+
+pub const TICKET_NAME: &str = "LSD Withdrawal Ticket";
+pub const TICKET_SYMBOL: &str = "LSDWT";
 
 pub const CAL_BASE: u64 = 1_000_000_000;
+pub const BPS_BASE: u64 = 10_000;
 pub const DEFAULT_RATE: u64 = 1_000_000_000;
 pub const DEFAULT_MIN_STAKE_AMOUNT: u64 = 10_000_000;
 pub const DEFAULT_PLATFORM_FEE_COMMISSION: u64 = 100_000_000;
 pub const DEFAULT_RATE_CHANGE_LIMIT: u64 = 1_000_000;
+pub const DEFAULT_INSTANT_UNSTAKE_FEE_COMMISSION: u64 = 3_000_000;
 pub const ERA_RATES_LEN_LIMIT: u8 = 10;
+pub const FEE_SPLITS_LEN_LIMIT: u8 = 5;
+
+// bump the version when a StakeManager layout change needs migrate_stake_manager to fill in
+// values, fields appended after era_rates read as zero without it
+pub const STAKE_MANAGER_VERSION: u8 = 1;
+pub const STAKE_MANAGER_SPACE: usize = 4096;
+
+// bits of StakeManager.paused
+pub const PAUSE_STAKE: u32 = 1 << 0;
+pub const PAUSE_UNSTAKE: u32 = 1 << 1;
+pub const PAUSE_WITHDRAW: u32 = 1 << 2;
+pub const PAUSE_INSTANT_UNSTAKE: u32 = 1 << 3;
+pub const PAUSE_CANCEL_UNSTAKE: u32 = 1 << 4;
+pub const PAUSE_ERA_NEW: u32 = 1 << 5;
+pub const PAUSE_ERA_BOND: u32 = 1 << 6;
+pub const PAUSE_ERA_UNBOND: u32 = 1 << 7;
+pub const PAUSE_ERA_WITHDRAW: u32 = 1 << 8;
+pub const PAUSE_ERA_ACTIVE: u32 = 1 << 9;
+pub const PAUSE_ALL: u32 = u32::MAX;
+
+// bits of PendingConfig.set, one per field of ConfigStakeManagerParams
+pub const CONFIG_MIN_STAKE_AMOUNT: u8 = 1 << 0;
+pub const CONFIG_PLATFORM_FEE_COMMISSION: u8 = 1 << 1;
+pub const CONFIG_RATE_CHANGE_LIMIT: u8 = 1 << 2;
+pub const CONFIG_INSTANT_UNSTAKE_FEE_COMMISSION: u8 = 1 << 3;
+pub const CONFIG_INSTANT_UNSTAKE_FEE_MODE: u8 = 1 << 4;
+pub const CONFIG_BUFFER_TARGET_BPS: u8 = 1 << 5;
+pub const CONFIG_CONFIG_DELAY: u8 = 1 << 6;
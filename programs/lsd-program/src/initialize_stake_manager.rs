@@ -1,6 +1,6 @@
 pub use crate::errors::Errors;
 pub use crate::StakeManager;
-use crate::{helper, EraStatus};
+use crate::{helper, EraStatus, InstantUnstakeFeeMode, PendingConfig};
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
@@ -16,7 +16,7 @@ pub struct InitializeStakeManager<'info> {
 
     #[account(
         init,
-        space = 2048,
+        space = helper::STAKE_MANAGER_SPACE,
         payer = rent_payer,
         seeds = [
             helper::STAKE_MANAGER_SEED,
@@ -111,6 +111,47 @@ impl<'info> InitializeStakeManager<'info> {
             active: 0,
             total_platform_fee: 0,
             era_rates: vec![],
+            instant_unstake_fee_commission: helper::DEFAULT_INSTANT_UNSTAKE_FEE_COMMISSION,
+            instant_unstake_fee_mode: InstantUnstakeFeeMode::LsdHolders,
+            buffer_target_bps: 0,
+            buffer: 0,
+            paused: 0,
+            operator: self.admin.key(),
+            pending_operator: Pubkey::default(),
+            fee_manager: self.admin.key(),
+            pending_fee_manager: Pubkey::default(),
+            pauser: self.admin.key(),
+            pending_pauser: Pubkey::default(),
+            metadata_authority: self.admin.key(),
+            pending_metadata_authority: Pubkey::default(),
+            config_delay: 0,
+            pending_config: PendingConfig::default(),
+            renounced: false,
+            fee_recipient: self.admin.key(),
+            fee_splits: vec![],
+            wind_down: false,
+            wind_down_idle: 0,
+            pending_unstake_amount: 0,
+            unbonding: 0,
+            legacy_unstake_accounts: false,
+            legacy_withdrawable_era: 0,
+            migration_staking_pool: Pubkey::default(),
+            migration_staking_program: Pubkey::default(),
+            migration_started_at: 0,
+            migration_unbonding: 0,
+            migration_reserved: 0,
+            migration_bonded: false,
+            migration_pool_unbonding: 0,
+            version: helper::STAKE_MANAGER_VERSION,
+            bonded_amount: 0,
+            unbonded_amount: 0,
+            catch_up_era: 0,
+            catch_up_shift: 0,
+            catch_up_unbonding_duration: 0,
+            pending_rebond: 0,
+            pending_rebond_era: 0,
+            buffer_unbonding: 0,
+            buffer_unbonding_era: 0,
             _reserved: [0u8; 256],
         });
 
@@ -1,8 +1,12 @@
 use anchor_lang::{prelude::*, Bumps};
 
 pub mod admin;
+pub mod close_stake_manager;
+pub mod config_timelock;
 pub mod era_active;
+pub mod era_advance;
 pub mod era_bond;
+pub mod era_catch_up;
 pub mod era_new;
 pub mod era_unbond;
 pub mod era_withdraw;
@@ -10,14 +14,27 @@ pub mod errors;
 pub mod helper;
 pub mod initialize_stake_manager;
 pub mod metadata;
+pub mod migrate_stake_manager;
+pub mod staker_cancel_unstake;
+pub mod staker_instant_unstake;
 pub mod staker_stake;
+pub mod staker_stake_for;
 pub mod staker_unstake;
+pub mod staker_unstake_with_ticket;
 pub mod staker_withdraw;
+pub mod staker_withdraw_batch;
+pub mod staker_withdraw_ticket;
+pub mod staking_pool_migration;
 pub mod states;
+pub mod sync_staking_pool;
 
 pub use crate::admin::*;
+pub use crate::close_stake_manager::*;
+pub use crate::config_timelock::*;
 pub use crate::era_active::*;
+pub use crate::era_advance::*;
 pub use crate::era_bond::*;
+pub use crate::era_catch_up::*;
 pub use crate::era_new::*;
 pub use crate::era_unbond::*;
 pub use crate::era_withdraw::*;
@@ -25,17 +42,24 @@ pub use crate::errors::Errors;
 pub use crate::helper::*;
 pub use crate::initialize_stake_manager::*;
 pub use crate::metadata::*;
+pub use crate::migrate_stake_manager::*;
+pub use crate::staker_cancel_unstake::*;
+pub use crate::staker_instant_unstake::*;
 pub use crate::staker_stake::*;
+pub use crate::staker_stake_for::*;
 pub use crate::staker_unstake::*;
+pub use crate::staker_unstake_with_ticket::*;
 pub use crate::staker_withdraw::*;
+pub use crate::staker_withdraw_batch::*;
+pub use crate::staker_withdraw_ticket::*;
+pub use crate::staking_pool_migration::*;
 pub use crate::states::*;
+pub use crate::sync_staking_pool::*;
 
 declare_id!("6UrZH8GHxgSHu13ZqUMxHwiUnezXSqnEKDVNEpY1cAPu");
 
 fn check_context<T: Bumps>(ctx: &Context<T>) -> Result<()> {
-    if !check_id(ctx.program_id) {
-        return err!(Errors::ProgramIdNotMatch);
-    }
+    check_program_id(ctx)?;
 
     if !ctx.remaining_accounts.is_empty() {
         return err!(Errors::RemainingAccountsNotMatch);
@@ -44,6 +68,14 @@ fn check_context<T: Bumps>(ctx: &Context<T>) -> Result<()> {
     Ok(())
 }
 
+fn check_program_id<T: Bumps>(ctx: &Context<T>) -> Result<()> {
+    if !check_id(ctx.program_id) {
+        return err!(Errors::ProgramIdNotMatch);
+    }
+
+    Ok(())
+}
+
 #[program]
 pub mod lsd_program {
 
@@ -62,6 +94,14 @@ pub mod lsd_program {
         Ok(())
     }
 
+    pub fn migrate_stake_manager(ctx: Context<MigrateStakeManager>) -> Result<()> {
+        check_context(&ctx)?;
+
+        ctx.accounts.process()?;
+
+        Ok(())
+    }
+
     // admin of stake manager
 
     pub fn transfer_stake_manager_admin(
@@ -83,6 +123,115 @@ pub mod lsd_program {
         Ok(())
     }
 
+    pub fn cancel_admin_transfer(ctx: Context<CancelAdminTransfer>) -> Result<()> {
+        check_context(&ctx)?;
+
+        ctx.accounts.process()?;
+
+        Ok(())
+    }
+
+    pub fn renounce_admin(ctx: Context<RenounceAdmin>) -> Result<()> {
+        check_context(&ctx)?;
+
+        ctx.accounts.process()?;
+
+        Ok(())
+    }
+
+    pub fn set_fee_recipient(
+        ctx: Context<SetFeeRecipient>,
+        new_fee_recipient: Pubkey,
+    ) -> Result<()> {
+        check_context(&ctx)?;
+
+        ctx.accounts.process(new_fee_recipient)?;
+
+        Ok(())
+    }
+
+    pub fn set_fee_splits<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SetFeeSplits<'info>>,
+        fee_splits: Vec<FeeSplit>,
+    ) -> Result<()> {
+        check_program_id(&ctx)?;
+
+        ctx.accounts.process(fee_splits, ctx.remaining_accounts)?;
+
+        Ok(())
+    }
+
+    pub fn set_era_seconds(ctx: Context<SetEraSeconds>, new_era_seconds: i64) -> Result<()> {
+        check_context(&ctx)?;
+
+        ctx.accounts.process(new_era_seconds)?;
+
+        Ok(())
+    }
+
+    pub fn start_wind_down(ctx: Context<StartWindDown>) -> Result<()> {
+        check_context(&ctx)?;
+
+        ctx.accounts.process()?;
+
+        Ok(())
+    }
+
+    pub fn clear_legacy_unstake_accounts(ctx: Context<ClearLegacyUnstakeAccounts>) -> Result<()> {
+        check_context(&ctx)?;
+
+        ctx.accounts.process()?;
+
+        Ok(())
+    }
+
+    pub fn close_stake_manager(ctx: Context<CloseStakeManager>) -> Result<()> {
+        check_context(&ctx)?;
+
+        ctx.accounts.process()?;
+
+        Ok(())
+    }
+
+    pub fn start_staking_pool_migration(ctx: Context<StartStakingPoolMigration>) -> Result<()> {
+        check_context(&ctx)?;
+
+        ctx.accounts.process()?;
+
+        Ok(())
+    }
+
+    pub fn finish_staking_pool_migration(ctx: Context<FinishStakingPoolMigration>) -> Result<()> {
+        check_context(&ctx)?;
+
+        ctx.accounts.process()?;
+
+        Ok(())
+    }
+
+    pub fn transfer_stake_manager_role(
+        ctx: Context<TransferStakeManagerRole>,
+        role: Role,
+        new_holder: Pubkey,
+    ) -> Result<()> {
+        check_context(&ctx)?;
+
+        ctx.accounts.process(role, new_holder)?;
+
+        Ok(())
+    }
+
+    pub fn accept_stake_manager_role(
+        ctx: Context<AcceptStakeManagerRole>,
+        role: Role,
+    ) -> Result<()> {
+        check_context(&ctx)?;
+
+        ctx.accounts.process(role)?;
+
+        Ok(())
+    }
+
     pub fn config_stake_manager(
         ctx: Context<ConfigStakeManager>,
         params: ConfigStakeManagerParams,
@@ -94,6 +243,41 @@ pub mod lsd_program {
         Ok(())
     }
 
+    pub fn propose_config(
+        ctx: Context<ProposeConfig>,
+        params: ConfigStakeManagerParams,
+    ) -> Result<()> {
+        check_context(&ctx)?;
+
+        ctx.accounts.process(params)?;
+
+        Ok(())
+    }
+
+    pub fn execute_config(ctx: Context<ExecuteConfig>) -> Result<()> {
+        check_context(&ctx)?;
+
+        ctx.accounts.process()?;
+
+        Ok(())
+    }
+
+    pub fn cancel_config(ctx: Context<CancelConfig>) -> Result<()> {
+        check_context(&ctx)?;
+
+        ctx.accounts.process()?;
+
+        Ok(())
+    }
+
+    pub fn set_paused(ctx: Context<SetPaused>, paused: u32) -> Result<()> {
+        check_context(&ctx)?;
+
+        ctx.accounts.process(paused)?;
+
+        Ok(())
+    }
+
     // metadata
     pub fn create_metadata(
         ctx: Context<CreateMetadataV1>,
@@ -103,20 +287,87 @@ pub mod lsd_program {
         ctx.accounts.process(params)
     }
 
+    pub fn update_metadata(
+        ctx: Context<UpdateMetadataV1>,
+        params: UpdateMetadataParams,
+    ) -> Result<()> {
+        check_context(&ctx)?;
+        ctx.accounts.process(params)
+    }
+
+    pub fn lock_metadata(ctx: Context<LockMetadataV1>) -> Result<()> {
+        check_context(&ctx)?;
+        ctx.accounts.process()
+    }
+
     // staker
 
-    pub fn stake(ctx: Context<Stake>, stake_amount: u64) -> Result<()> {
+    pub fn stake(
+        ctx: Context<Stake>,
+        stake_amount: u64,
+        min_lsd_out: u64,
+        expected_era: Option<u64>,
+        deadline: Option<i64>,
+    ) -> Result<()> {
+        check_context(&ctx)?;
+
+        ctx.accounts
+            .process(stake_amount, min_lsd_out, expected_era, deadline)?;
+
+        Ok(())
+    }
+
+    pub fn stake_for(
+        ctx: Context<StakeFor>,
+        stake_amount: u64,
+        min_lsd_out: u64,
+        expected_era: Option<u64>,
+        deadline: Option<i64>,
+    ) -> Result<()> {
         check_context(&ctx)?;
 
-        ctx.accounts.process(stake_amount)?;
+        ctx.accounts
+            .process(stake_amount, min_lsd_out, expected_era, deadline)?;
 
         Ok(())
     }
 
-    pub fn unstake(ctx: Context<Unstake>, unstake_amount: u64) -> Result<()> {
+    pub fn unstake(
+        ctx: Context<Unstake>,
+        unstake_amount: u64,
+        min_staking_out: u64,
+        expected_era: Option<u64>,
+        deadline: Option<i64>,
+    ) -> Result<()> {
         check_context(&ctx)?;
 
-        ctx.accounts.process(unstake_amount)?;
+        ctx.accounts.process(
+            unstake_amount,
+            min_staking_out,
+            expected_era,
+            deadline,
+            ctx.bumps.unstake_account,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn unstake_with_ticket(
+        ctx: Context<UnstakeWithTicket>,
+        unstake_amount: u64,
+        min_staking_out: u64,
+        expected_era: Option<u64>,
+        deadline: Option<i64>,
+    ) -> Result<()> {
+        check_context(&ctx)?;
+
+        ctx.accounts.process(
+            unstake_amount,
+            min_staking_out,
+            expected_era,
+            deadline,
+            ctx.bumps.unstake_account,
+        )?;
 
         Ok(())
     }
@@ -129,6 +380,55 @@ pub mod lsd_program {
         Ok(())
     }
 
+    pub fn withdraw_legacy(ctx: Context<WithdrawLegacy>) -> Result<()> {
+        check_context(&ctx)?;
+
+        ctx.accounts.process()?;
+
+        Ok(())
+    }
+
+    pub fn withdraw_ticket(ctx: Context<WithdrawTicket>) -> Result<()> {
+        check_context(&ctx)?;
+
+        ctx.accounts.process()?;
+
+        Ok(())
+    }
+
+    pub fn cancel_unstake(ctx: Context<CancelUnstake>) -> Result<()> {
+        check_context(&ctx)?;
+
+        ctx.accounts.process()?;
+
+        Ok(())
+    }
+
+    pub fn withdraw_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, WithdrawBatch<'info>>,
+    ) -> Result<()> {
+        check_program_id(&ctx)?;
+
+        ctx.accounts.process(ctx.remaining_accounts)?;
+
+        Ok(())
+    }
+
+    pub fn instant_unstake(
+        ctx: Context<InstantUnstake>,
+        unstake_amount: u64,
+        min_staking_out: u64,
+        expected_era: Option<u64>,
+        deadline: Option<i64>,
+    ) -> Result<()> {
+        check_context(&ctx)?;
+
+        ctx.accounts
+            .process(unstake_amount, min_staking_out, expected_era, deadline)?;
+
+        Ok(())
+    }
+
     // era
 
     pub fn era_new(ctx: Context<EraNew>) -> Result<()> {
@@ -139,6 +439,14 @@ pub mod lsd_program {
         Ok(())
     }
 
+    pub fn era_catch_up(ctx: Context<EraCatchUp>) -> Result<()> {
+        check_context(&ctx)?;
+
+        ctx.accounts.process()?;
+
+        Ok(())
+    }
+
     pub fn era_bond(ctx: Context<EraBond>) -> Result<()> {
         check_context(&ctx)?;
 
@@ -163,7 +471,39 @@ pub mod lsd_program {
         Ok(())
     }
 
-    pub fn era_active(ctx: Context<EraActive>) -> Result<()> {
+    pub fn era_active<'info>(ctx: Context<'_, '_, 'info, 'info, EraActive<'info>>) -> Result<()> {
+        check_program_id(&ctx)?;
+
+        ctx.accounts.process(ctx.remaining_accounts)?;
+
+        Ok(())
+    }
+
+    pub fn era_advance<'info>(ctx: Context<'_, '_, 'info, 'info, EraAdvance<'info>>) -> Result<()> {
+        check_program_id(&ctx)?;
+
+        ctx.accounts.process(ctx.remaining_accounts)?;
+
+        Ok(())
+    }
+
+    pub fn migrate_unbond(ctx: Context<MigrateUnbond>) -> Result<()> {
+        check_context(&ctx)?;
+
+        ctx.accounts.process()?;
+
+        Ok(())
+    }
+
+    pub fn migrate_withdraw(ctx: Context<MigrateWithdraw>) -> Result<()> {
+        check_context(&ctx)?;
+
+        ctx.accounts.process()?;
+
+        Ok(())
+    }
+
+    pub fn sync_staking_pool(ctx: Context<SyncStakingPool>) -> Result<()> {
         check_context(&ctx)?;
 
         ctx.accounts.process()?;
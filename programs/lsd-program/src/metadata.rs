@@ -1,10 +1,13 @@
-use crate::{helper, Errors, StakeManager};
+use crate::{helper, Errors, Role, StakeManager};
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::TokenInterface;
 use anchor_spl::{metadata::Metadata, token_interface::Mint};
 use mpl_token_metadata::{
-    instructions::CreateV1Cpi, instructions::CreateV1CpiAccounts,
-    instructions::CreateV1InstructionArgs, types::TokenStandard,
+    accounts::Metadata as MetadataAccount, instructions::CreateV1Cpi,
+    instructions::CreateV1CpiAccounts, instructions::CreateV1InstructionArgs,
+    instructions::UpdateV1Cpi, instructions::UpdateV1CpiAccounts,
+    instructions::UpdateV1InstructionArgs, types::CollectionDetailsToggle, types::CollectionToggle,
+    types::Data, types::RuleSetToggle, types::TokenStandard, types::UsesToggle,
 };
 
 #[derive(Accounts)]
@@ -12,11 +15,8 @@ pub struct CreateMetadataV1<'info> {
     #[account(mut)]
     pub fee_and_rent_payer: Signer<'info>,
 
-    pub admin: Signer<'info>,
-    #[account(
-        mut,
-        has_one = admin @ Errors::AdminNotMatch
-    )]
+    pub authority: Signer<'info>,
+    #[account(mut)]
     pub stake_manager: Box<Account<'info, StakeManager>>,
 
     #[account(
@@ -49,9 +49,31 @@ pub struct CreateMetadataParams {
     token_uri: String,
 }
 
+#[event]
+pub struct EventCreateMetadata {
+    pub stake_manager: Pubkey,
+    pub authority: Pubkey,
+    pub lsd_token_mint: Pubkey,
+    pub metadata_account: Pubkey,
+    pub token_name: String,
+    pub token_symbol: String,
+    pub token_uri: String,
+}
+
 impl<'info> CreateMetadataV1<'info> {
     pub fn process(&mut self, config_metadata_params: CreateMetadataParams) -> Result<()> {
-        msg!("Creating metadata v1");
+        self.stake_manager
+            .check_role(Role::MetadataAuthority, &self.authority.key())?;
+
+        let event = EventCreateMetadata {
+            stake_manager: self.stake_manager.key(),
+            authority: self.authority.key(),
+            lsd_token_mint: self.lsd_token_mint.key(),
+            metadata_account: self.metadata_account.key(),
+            token_name: config_metadata_params.token_name.clone(),
+            token_symbol: config_metadata_params.token_symbol.clone(),
+            token_uri: config_metadata_params.token_uri.clone(),
+        };
 
         let signer_seeds: &[&[&[u8]]] = &[&[
             helper::STAKE_MANAGER_SEED,
@@ -91,6 +113,220 @@ impl<'info> CreateMetadataV1<'info> {
         )
         .invoke_signed(signer_seeds)?;
 
+        emit!(event);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct UpdateMetadataV1<'info> {
+    #[account(mut)]
+    pub fee_and_rent_payer: Signer<'info>,
+
+    pub authority: Signer<'info>,
+    pub stake_manager: Box<Account<'info, StakeManager>>,
+
+    #[account(
+        address = stake_manager.lsd_token_mint @Errors::LsdTokenMintAccountNotMatch
+    )]
+    pub lsd_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: Validate address by deriving pda
+    #[account(
+         mut,
+         seeds = [b"metadata", metadata_program.key().as_ref(), lsd_token_mint.key().as_ref()],
+         bump,
+         seeds::program = metadata_program.key(),
+     )]
+    pub metadata_account: UncheckedAccount<'info>,
+
+    pub metadata_program: Program<'info, Metadata>,
+    pub system_program: Program<'info, System>,
+    /// CHECK:
+    pub sysvar_instruction: UncheckedAccount<'info>,
+}
+
+#[repr(C)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct UpdateMetadataParams {
+    token_name: String,
+    token_symbol: String,
+    token_uri: String,
+}
+
+#[event]
+pub struct EventUpdateMetadata {
+    pub stake_manager: Pubkey,
+    pub authority: Pubkey,
+    pub lsd_token_mint: Pubkey,
+    pub old_token_name: String,
+    pub new_token_name: String,
+    pub old_token_symbol: String,
+    pub new_token_symbol: String,
+    pub old_token_uri: String,
+    pub new_token_uri: String,
+}
+
+impl<'info> UpdateMetadataV1<'info> {
+    pub fn process(&mut self, update_metadata_params: UpdateMetadataParams) -> Result<()> {
+        self.stake_manager
+            .check_role(Role::MetadataAuthority, &self.authority.key())?;
+
+        let old_metadata = read_metadata(&self.metadata_account)?;
+        let event = EventUpdateMetadata {
+            stake_manager: self.stake_manager.key(),
+            authority: self.authority.key(),
+            lsd_token_mint: self.lsd_token_mint.key(),
+            old_token_name: trim_metadata_str(&old_metadata.name),
+            new_token_name: update_metadata_params.token_name.clone(),
+            old_token_symbol: trim_metadata_str(&old_metadata.symbol),
+            new_token_symbol: update_metadata_params.token_symbol.clone(),
+            old_token_uri: trim_metadata_str(&old_metadata.uri),
+            new_token_uri: update_metadata_params.token_uri.clone(),
+        };
+
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            helper::STAKE_MANAGER_SEED,
+            &self.stake_manager.creator.to_bytes(),
+            &[self.stake_manager.index],
+            &[self.stake_manager.pool_seed_bump],
+        ]];
+        UpdateV1Cpi::new(
+            &self.metadata_program.to_account_info(),
+            UpdateV1CpiAccounts {
+                authority: &self.stake_manager.to_account_info(),
+                delegate_record: None,
+                token: None,
+                mint: &self.lsd_token_mint.to_account_info(),
+                metadata: &self.metadata_account.to_account_info(),
+                edition: None,
+                payer: &self.fee_and_rent_payer.to_account_info(),
+                system_program: &self.system_program.to_account_info(),
+                sysvar_instructions: &self.sysvar_instruction.to_account_info(),
+                authorization_rules_program: None,
+                authorization_rules: None,
+            },
+            UpdateV1InstructionArgs {
+                new_update_authority: None,
+                data: Some(Data {
+                    name: update_metadata_params.token_name,
+                    symbol: update_metadata_params.token_symbol,
+                    uri: update_metadata_params.token_uri,
+                    seller_fee_basis_points: 0,
+                    creators: None,
+                }),
+                primary_sale_happened: None,
+                is_mutable: None,
+                collection: CollectionToggle::None,
+                collection_details: CollectionDetailsToggle::None,
+                uses: UsesToggle::None,
+                rule_set: RuleSetToggle::None,
+                authorization_data: None,
+            },
+        )
+        .invoke_signed(signer_seeds)?;
+
+        emit!(event);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct LockMetadataV1<'info> {
+    #[account(mut)]
+    pub fee_and_rent_payer: Signer<'info>,
+
+    pub authority: Signer<'info>,
+    pub stake_manager: Box<Account<'info, StakeManager>>,
+
+    #[account(
+        address = stake_manager.lsd_token_mint @Errors::LsdTokenMintAccountNotMatch
+    )]
+    pub lsd_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: Validate address by deriving pda
+    #[account(
+         mut,
+         seeds = [b"metadata", metadata_program.key().as_ref(), lsd_token_mint.key().as_ref()],
+         bump,
+         seeds::program = metadata_program.key(),
+     )]
+    pub metadata_account: UncheckedAccount<'info>,
+
+    pub metadata_program: Program<'info, Metadata>,
+    pub system_program: Program<'info, System>,
+    /// CHECK:
+    pub sysvar_instruction: UncheckedAccount<'info>,
+}
+
+#[event]
+pub struct EventLockMetadata {
+    pub stake_manager: Pubkey,
+    pub authority: Pubkey,
+    pub lsd_token_mint: Pubkey,
+    pub old_is_mutable: bool,
+    pub new_is_mutable: bool,
+}
+
+impl<'info> LockMetadataV1<'info> {
+    pub fn process(&mut self) -> Result<()> {
+        self.stake_manager
+            .check_role(Role::MetadataAuthority, &self.authority.key())?;
+
+        let old_metadata = read_metadata(&self.metadata_account)?;
+
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            helper::STAKE_MANAGER_SEED,
+            &self.stake_manager.creator.to_bytes(),
+            &[self.stake_manager.index],
+            &[self.stake_manager.pool_seed_bump],
+        ]];
+        // once immutable the metadata can never be updated again
+        UpdateV1Cpi::new(
+            &self.metadata_program.to_account_info(),
+            UpdateV1CpiAccounts {
+                authority: &self.stake_manager.to_account_info(),
+                delegate_record: None,
+                token: None,
+                mint: &self.lsd_token_mint.to_account_info(),
+                metadata: &self.metadata_account.to_account_info(),
+                edition: None,
+                payer: &self.fee_and_rent_payer.to_account_info(),
+                system_program: &self.system_program.to_account_info(),
+                sysvar_instructions: &self.sysvar_instruction.to_account_info(),
+                authorization_rules_program: None,
+                authorization_rules: None,
+            },
+            UpdateV1InstructionArgs {
+                new_update_authority: None,
+                data: None,
+                primary_sale_happened: None,
+                is_mutable: Some(false),
+                collection: CollectionToggle::None,
+                collection_details: CollectionDetailsToggle::None,
+                uses: UsesToggle::None,
+                rule_set: RuleSetToggle::None,
+                authorization_data: None,
+            },
+        )
+        .invoke_signed(signer_seeds)?;
+
+        emit!(EventLockMetadata {
+            stake_manager: self.stake_manager.key(),
+            authority: self.authority.key(),
+            lsd_token_mint: self.lsd_token_mint.key(),
+            old_is_mutable: old_metadata.is_mutable,
+            new_is_mutable: false,
+        });
         Ok(())
     }
 }
+
+fn read_metadata(metadata_account: &AccountInfo) -> Result<MetadataAccount> {
+    MetadataAccount::try_from(metadata_account).map_err(|_| error!(Errors::InvalidMetadataAccount))
+}
+
+// token metadata pads name, symbol and uri with zero bytes
+fn trim_metadata_str(value: &str) -> String {
+    value.trim_end_matches(char::from(0)).to_string()
+}
@@ -0,0 +1,78 @@
+use crate::{helper, Errors, StakeManager};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct MigrateStakeManager<'info> {
+    #[account(mut)]
+    pub rent_payer: Signer<'info>,
+
+    #[account(
+        mut,
+        realloc = helper::STAKE_MANAGER_SPACE,
+        realloc::payer = rent_payer,
+        realloc::zero = false,
+    )]
+    pub stake_manager: Box<Account<'info, StakeManager>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct EventMigrateStakeManager {
+    pub stake_manager: Pubkey,
+    pub rent_payer: Pubkey,
+    pub old_version: u8,
+    pub new_version: u8,
+    pub space: u64,
+}
+
+impl<'info> MigrateStakeManager<'info> {
+    pub fn process(&mut self) -> Result<()> {
+        let old_version = self.stake_manager.version;
+        require_gt!(
+            helper::STAKE_MANAGER_VERSION,
+            old_version,
+            Errors::StakeManagerVersionIsLatest
+        );
+
+        // version 0 read every field appended after era_rates as zero, only fill in the ones
+        // still unset so accounts initialized with those fields keep their values
+        if old_version < 1 {
+            let admin = self.stake_manager.admin;
+            let stake_manager = &mut self.stake_manager;
+            if !stake_manager.renounced {
+                if stake_manager.operator == Pubkey::default() {
+                    stake_manager.operator = admin;
+                }
+                if stake_manager.fee_manager == Pubkey::default() {
+                    stake_manager.fee_manager = admin;
+                }
+                if stake_manager.pauser == Pubkey::default() {
+                    stake_manager.pauser = admin;
+                }
+                if stake_manager.metadata_authority == Pubkey::default() {
+                    stake_manager.metadata_authority = admin;
+                }
+            }
+            if stake_manager.fee_recipient == Pubkey::default() {
+                stake_manager.fee_recipient = admin;
+            }
+            // unstake accounts were keypairs and are not part of pending_unstake_amount. Version 0
+            // never changed unbonding_duration, so none of them matures later than this.
+            stake_manager.legacy_unstake_accounts = true;
+            stake_manager.legacy_withdrawable_era =
+                stake_manager.latest_era + stake_manager.unbonding_duration;
+        }
+
+        self.stake_manager.version = helper::STAKE_MANAGER_VERSION;
+
+        emit!(EventMigrateStakeManager {
+            stake_manager: self.stake_manager.key(),
+            rent_payer: self.rent_payer.key(),
+            old_version,
+            new_version: helper::STAKE_MANAGER_VERSION,
+            space: helper::STAKE_MANAGER_SPACE as u64,
+        });
+        Ok(())
+    }
+}
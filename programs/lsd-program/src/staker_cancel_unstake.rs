@@ -0,0 +1,131 @@
+use crate::{helper, EraStatus, Errors, StakeManager, UnstakeAccount};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{mint_to, Mint, MintTo, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct CancelUnstake<'info> {
+    #[account(
+        address = unstake_account.user @ Errors::UnstakeUserNotMatch
+    )]
+    pub user: Signer<'info>,
+
+    #[account(mut)]
+    pub rent_payer: Signer<'info>,
+
+    #[account(mut)]
+    pub stake_manager: Box<Account<'info, StakeManager>>,
+
+    #[account(
+        mut,
+        close = rent_payer,
+        has_one = stake_manager @Errors::InvalidUnstakeAccount,
+        constraint = unstake_account.ticket_mint == Pubkey::default() @Errors::UnstakeAccountHasTicket,
+        seeds = [
+            helper::UNSTAKE_ACCOUNT_SEED,
+            &stake_manager.key().to_bytes(),
+            &unstake_account.user.to_bytes(),
+            &unstake_account.nonce.to_le_bytes(),
+        ],
+        bump = unstake_account.bump,
+    )]
+    pub unstake_account: Account<'info, UnstakeAccount>,
+
+    #[account(
+        mut,
+        address = stake_manager.lsd_token_mint @Errors::LsdTokenMintAccountNotMatch
+    )]
+    pub lsd_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer = rent_payer,
+        associated_token::mint = lsd_token_mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub user_lsd_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct EventCancelUnstake {
+    pub era: u64,
+    pub user: Pubkey,
+    pub unstake_account: Pubkey,
+    pub staking_token_amount: u64,
+    pub lsd_token_amount: u64,
+    pub stake_manager: Pubkey,
+}
+
+impl<'info> CancelUnstake<'info> {
+    pub fn process(&mut self) -> Result<()> {
+        self.stake_manager
+            .check_not_paused(helper::PAUSE_CANCEL_UNSTAKE)?;
+        require!(!self.stake_manager.wind_down, Errors::WindDown);
+
+        let staking_token_amount = self.unstake_account.amount;
+        require_gt!(staking_token_amount, 0, Errors::UnstakeAccountAmountZero);
+
+        if self.unstake_account.era == self.stake_manager.latest_era {
+            // not netted yet, the amount is still part of this era's unbond
+            self.stake_manager.era_unbond -= staking_token_amount;
+        } else {
+            // already netted by the cycle of an earlier era, its unbond may still be in flight.
+            // It earns again right away and is bonded once the unbond landed.
+            require!(
+                self.stake_manager.era_status == EraStatus::ActiveUpdated,
+                Errors::EraStatusNotMatch
+            );
+
+            let withdrawable_era = self
+                .stake_manager
+                .calc_withdrawable_era(&self.unstake_account);
+            self.stake_manager.pending_rebond += staking_token_amount;
+            self.stake_manager.pending_rebond_era =
+                self.stake_manager.pending_rebond_era.max(withdrawable_era);
+            self.stake_manager.release_landed_unbonds();
+        }
+        self.stake_manager.active += staking_token_amount;
+
+        let lsd_token_amount = self
+            .stake_manager
+            .calc_lsd_token_amount(staking_token_amount)?;
+
+        self.unstake_account.amount = 0;
+        self.stake_manager.pending_unstake_amount -= staking_token_amount;
+
+        // mint lsd token
+        mint_to(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                MintTo {
+                    mint: self.lsd_token_mint.to_account_info(),
+                    to: self.user_lsd_token_account.to_account_info(),
+                    authority: self.stake_manager.to_account_info(),
+                },
+                &[&[
+                    helper::STAKE_MANAGER_SEED,
+                    &self.stake_manager.creator.to_bytes(),
+                    &[self.stake_manager.index],
+                    &[self.stake_manager.pool_seed_bump],
+                ]],
+            ),
+            lsd_token_amount,
+        )?;
+
+        emit!(EventCancelUnstake {
+            era: self.stake_manager.latest_era,
+            user: self.user.key(),
+            unstake_account: self.unstake_account.key(),
+            staking_token_amount,
+            lsd_token_amount,
+            stake_manager: self.stake_manager.key(),
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,212 @@
+use crate::{helper, Errors, InstantUnstakeFeeMode, StakeManager};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{
+    burn, transfer_checked, Burn, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+#[derive(Accounts)]
+pub struct InstantUnstake<'info> {
+    pub user: Signer<'info>,
+
+    #[account(mut)]
+    pub rent_payer: Signer<'info>,
+
+    /// CHECK: stake_manager
+    #[account(
+        address = stake_manager.fee_recipient_or_admin() @Errors::PlatformFeeRecipientNotMatch,
+    )]
+    pub fee_recipient: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub stake_manager: Box<Account<'info, StakeManager>>,
+
+    #[account(
+        mut,
+        address = stake_manager.lsd_token_mint @Errors::LsdTokenMintAccountNotMatch,
+    )]
+    pub lsd_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        address = stake_manager.staking_token_mint @Errors::StakingTokenMintAccountNotMatch
+    )]
+    pub staking_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = lsd_token_mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub user_lsd_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = rent_payer,
+        associated_token::mint = staking_token_mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub user_staking_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = staking_token_mint,
+        associated_token::authority = stake_manager,
+        associated_token::token_program = token_program,
+    )]
+    pub stake_manager_staking_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // only required when a fee is transferred in platform mode
+    #[account(
+        mut,
+        associated_token::mint = lsd_token_mint,
+        associated_token::authority = fee_recipient,
+        associated_token::token_program = token_program,
+    )]
+    pub platform_fee_recipient: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct EventInstantUnstake {
+    pub era: u64,
+    pub staker: Pubkey,
+    pub unstake_amount: u64,
+    pub staking_token_amount: u64,
+    pub fee_amount: u64,
+    pub fee_mode: InstantUnstakeFeeMode,
+    pub stake_manager: Pubkey,
+}
+
+impl<'info> InstantUnstake<'info> {
+    pub fn process(
+        &mut self,
+        unstake_amount: u64,
+        min_staking_out: u64,
+        expected_era: Option<u64>,
+        deadline: Option<i64>,
+    ) -> Result<()> {
+        self.stake_manager
+            .check_not_paused(helper::PAUSE_INSTANT_UNSTAKE)?;
+
+        require_gt!(unstake_amount, 0, Errors::UnstakeAmountIsZero);
+        self.stake_manager
+            .check_era_and_deadline(expected_era, deadline)?;
+
+        require_gte!(
+            self.user_lsd_token_account.amount,
+            unstake_amount,
+            Errors::BalanceNotEnough
+        );
+
+        let fee_amount = self
+            .stake_manager
+            .calc_instant_unstake_fee(unstake_amount)?;
+        let staking_token_amount = self
+            .stake_manager
+            .calc_staking_token_amount(unstake_amount - fee_amount)?;
+        require_gt!(staking_token_amount, 0, Errors::UnstakeAccountAmountZero);
+        require_gte!(
+            staking_token_amount,
+            min_staking_out,
+            Errors::StakingTokenAmountTooLow
+        );
+
+        require_gte!(
+            self.stake_manager.calc_instant_unstake_liquidity(),
+            staking_token_amount,
+            Errors::BufferNotEnough
+        );
+        require_gte!(
+            self.stake_manager_staking_token_account.amount,
+            staking_token_amount,
+            Errors::BufferNotEnough
+        );
+
+        // net the exit against this era's deposits first, then draw the rest from the buffer,
+        // so it never reaches the staking_program
+        let from_idle_deposit = staking_token_amount.min(self.stake_manager.calc_idle_deposit());
+        self.stake_manager.era_unbond += from_idle_deposit;
+        self.stake_manager.buffer -= staking_token_amount - from_idle_deposit;
+        self.stake_manager.active -= staking_token_amount;
+
+        // fee in lsd holders mode is burned along with the rest, leaving its value in active
+        let burn_amount = match self.stake_manager.instant_unstake_fee_mode {
+            InstantUnstakeFeeMode::LsdHolders => unstake_amount,
+            InstantUnstakeFeeMode::Platform => {
+                if fee_amount > 0 {
+                    let platform_fee_recipient = self
+                        .platform_fee_recipient
+                        .as_ref()
+                        .ok_or(error!(Errors::PlatformFeeRecipientNotMatch))?;
+                    transfer_checked(
+                        CpiContext::new(
+                            self.token_program.to_account_info(),
+                            TransferChecked {
+                                from: self.user_lsd_token_account.to_account_info(),
+                                mint: self.lsd_token_mint.to_account_info(),
+                                to: platform_fee_recipient.to_account_info(),
+                                authority: self.user.to_account_info(),
+                            },
+                        ),
+                        fee_amount,
+                        self.lsd_token_mint.decimals,
+                    )?;
+
+                    self.stake_manager.total_platform_fee += fee_amount;
+                }
+
+                unstake_amount - fee_amount
+            }
+        };
+
+        // burn lsd token
+        burn(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                Burn {
+                    mint: self.lsd_token_mint.to_account_info(),
+                    from: self.user_lsd_token_account.to_account_info(),
+                    authority: self.user.to_account_info(),
+                },
+            ),
+            burn_amount,
+        )?;
+
+        transfer_checked(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: self.stake_manager_staking_token_account.to_account_info(),
+                    mint: self.staking_token_mint.to_account_info(),
+                    to: self.user_staking_token_account.to_account_info(),
+                    authority: self.stake_manager.to_account_info(),
+                },
+                &[&[
+                    helper::STAKE_MANAGER_SEED,
+                    &self.stake_manager.creator.to_bytes(),
+                    &[self.stake_manager.index],
+                    &[self.stake_manager.pool_seed_bump],
+                ]],
+            ),
+            staking_token_amount,
+            self.staking_token_mint.decimals,
+        )?;
+
+        emit!(EventInstantUnstake {
+            era: self.stake_manager.latest_era,
+            staker: self.user.key(),
+            unstake_amount,
+            staking_token_amount,
+            fee_amount,
+            fee_mode: self.stake_manager.instant_unstake_fee_mode,
+            stake_manager: self.stake_manager.key(),
+        });
+
+        Ok(())
+    }
+}
@@ -60,61 +60,48 @@ pub struct Stake<'info> {
 pub struct EventStake {
     pub era: u64,
     pub staker: Pubkey,
+    pub beneficiary: Pubkey,
     pub stake_amount: u64,
     pub lsd_token_amount: u64,
     pub stake_manager: Pubkey,
 }
 
+pub struct StakeArgs {
+    pub stake_amount: u64,
+    pub min_lsd_out: u64,
+    pub expected_era: Option<u64>,
+    pub deadline: Option<i64>,
+}
+
 impl<'info> Stake<'info> {
-    pub fn process(&mut self, stake_amount: u64) -> Result<()> {
-        require_gte!(
-            stake_amount,
-            self.stake_manager.min_stake_amount,
-            Errors::StakeAmountTooLow
-        );
-
-        // transfer staking token to the pool
-        let transfer_from_user_to_pool_cpi_context = CpiContext::new(
-            self.token_program.to_account_info(),
-            TransferChecked {
-                from: self.user_staking_token_account.to_account_info(),
-                mint: self.staking_token_mint.to_account_info(),
-                to: self.stake_manager_staking_token_account.to_account_info(),
-                authority: self.user.to_account_info(),
+    pub fn process(
+        &mut self,
+        stake_amount: u64,
+        min_lsd_out: u64,
+        expected_era: Option<u64>,
+        deadline: Option<i64>,
+    ) -> Result<()> {
+        let lsd_token_amount = stake_to(
+            &mut self.stake_manager,
+            &self.user,
+            &self.lsd_token_mint,
+            &self.staking_token_mint,
+            &self.user_lsd_token_account,
+            &self.user_staking_token_account,
+            &self.stake_manager_staking_token_account,
+            &self.token_program,
+            StakeArgs {
+                stake_amount,
+                min_lsd_out,
+                expected_era,
+                deadline,
             },
-        );
-        transfer_checked(
-            transfer_from_user_to_pool_cpi_context,
-            stake_amount,
-            self.staking_token_mint.decimals,
-        )?;
-
-        let lsd_token_amount = self.stake_manager.calc_lsd_token_amount(stake_amount)?;
-        self.stake_manager.era_bond += stake_amount;
-        self.stake_manager.active += stake_amount;
-
-        // mint lsd token
-        mint_to(
-            CpiContext::new_with_signer(
-                self.token_program.to_account_info(),
-                MintTo {
-                    mint: self.lsd_token_mint.to_account_info(),
-                    to: self.user_lsd_token_account.to_account_info(),
-                    authority: self.stake_manager.to_account_info(),
-                },
-                &[&[
-                    helper::STAKE_MANAGER_SEED,
-                    &self.stake_manager.creator.to_bytes(),
-                    &[self.stake_manager.index],
-                    &[self.stake_manager.pool_seed_bump],
-                ]],
-            ),
-            lsd_token_amount,
         )?;
 
         emit!(EventStake {
             era: self.stake_manager.latest_era,
             staker: self.user.key(),
+            beneficiary: self.user.key(),
             stake_amount,
             lsd_token_amount,
             stake_manager: self.stake_manager.key(),
@@ -122,3 +109,73 @@ impl<'info> Stake<'info> {
         Ok(())
     }
 }
+
+// moves the user's staking token into the pool and mints the lsd token to the beneficiary's account
+#[allow(clippy::too_many_arguments)]
+pub fn stake_to<'info>(
+    stake_manager: &mut Account<'info, StakeManager>,
+    user: &Signer<'info>,
+    lsd_token_mint: &InterfaceAccount<'info, Mint>,
+    staking_token_mint: &InterfaceAccount<'info, Mint>,
+    beneficiary_lsd_token_account: &InterfaceAccount<'info, TokenAccount>,
+    user_staking_token_account: &InterfaceAccount<'info, TokenAccount>,
+    stake_manager_staking_token_account: &InterfaceAccount<'info, TokenAccount>,
+    token_program: &Interface<'info, TokenInterface>,
+    args: StakeArgs,
+) -> Result<u64> {
+    stake_manager.check_not_paused(helper::PAUSE_STAKE)?;
+    require!(!stake_manager.wind_down, Errors::WindDown);
+
+    require_gte!(
+        args.stake_amount,
+        stake_manager.min_stake_amount,
+        Errors::StakeAmountTooLow
+    );
+    stake_manager.check_era_and_deadline(args.expected_era, args.deadline)?;
+
+    // transfer staking token to the pool
+    let transfer_from_user_to_pool_cpi_context = CpiContext::new(
+        token_program.to_account_info(),
+        TransferChecked {
+            from: user_staking_token_account.to_account_info(),
+            mint: staking_token_mint.to_account_info(),
+            to: stake_manager_staking_token_account.to_account_info(),
+            authority: user.to_account_info(),
+        },
+    );
+    transfer_checked(
+        transfer_from_user_to_pool_cpi_context,
+        args.stake_amount,
+        staking_token_mint.decimals,
+    )?;
+
+    let lsd_token_amount = stake_manager.calc_lsd_token_amount(args.stake_amount)?;
+    require_gte!(
+        lsd_token_amount,
+        args.min_lsd_out,
+        Errors::LsdTokenAmountTooLow
+    );
+    stake_manager.era_bond += args.stake_amount;
+    stake_manager.active += args.stake_amount;
+
+    // mint lsd token
+    mint_to(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            MintTo {
+                mint: lsd_token_mint.to_account_info(),
+                to: beneficiary_lsd_token_account.to_account_info(),
+                authority: stake_manager.to_account_info(),
+            },
+            &[&[
+                helper::STAKE_MANAGER_SEED,
+                &stake_manager.creator.to_bytes(),
+                &[stake_manager.index],
+                &[stake_manager.pool_seed_bump],
+            ]],
+        ),
+        lsd_token_amount,
+    )?;
+
+    Ok(lsd_token_amount)
+}
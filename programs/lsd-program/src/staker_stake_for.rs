@@ -0,0 +1,95 @@
+use crate::{stake_to, Errors, EventStake, StakeArgs, StakeManager};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct StakeFor<'info> {
+    pub user: Signer<'info>,
+
+    /// CHECK: only used as the authority of the minted lsd token account
+    pub beneficiary: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub rent_payer: Signer<'info>,
+
+    #[account(mut)]
+    pub stake_manager: Box<Account<'info, StakeManager>>,
+
+    #[account(
+        mut,
+        address = stake_manager.lsd_token_mint @Errors::LsdTokenMintAccountNotMatch
+    )]
+    pub lsd_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        address = stake_manager.staking_token_mint @Errors::StakingTokenMintAccountNotMatch
+    )]
+    pub staking_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer  = rent_payer,
+        associated_token::mint = lsd_token_mint,
+        associated_token::authority = beneficiary,
+        associated_token::token_program = token_program,
+    )]
+    pub beneficiary_lsd_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = staking_token_mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub user_staking_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = staking_token_mint,
+        associated_token::authority = stake_manager,
+        associated_token::token_program = token_program,
+    )]
+    pub stake_manager_staking_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> StakeFor<'info> {
+    pub fn process(
+        &mut self,
+        stake_amount: u64,
+        min_lsd_out: u64,
+        expected_era: Option<u64>,
+        deadline: Option<i64>,
+    ) -> Result<()> {
+        let lsd_token_amount = stake_to(
+            &mut self.stake_manager,
+            &self.user,
+            &self.lsd_token_mint,
+            &self.staking_token_mint,
+            &self.beneficiary_lsd_token_account,
+            &self.user_staking_token_account,
+            &self.stake_manager_staking_token_account,
+            &self.token_program,
+            StakeArgs {
+                stake_amount,
+                min_lsd_out,
+                expected_era,
+                deadline,
+            },
+        )?;
+
+        emit!(EventStake {
+            era: self.stake_manager.latest_era,
+            staker: self.user.key(),
+            beneficiary: self.beneficiary.key(),
+            stake_amount,
+            lsd_token_amount,
+            stake_manager: self.stake_manager.key(),
+        });
+        Ok(())
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{Errors, StakeManager, UnstakeAccount};
+use crate::{helper, Errors, StakeManager, UnstakeAccount, UnstakeNonceAccount};
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token_interface::{burn, Burn, Mint, TokenAccount, TokenInterface};
@@ -27,11 +27,31 @@ pub struct Unstake<'info> {
     )]
     pub user_lsd_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
+    #[account(
+        init_if_needed,
+        space = 8 + std::mem::size_of::<UnstakeNonceAccount>(),
+        payer = rent_payer,
+        seeds = [
+            helper::UNSTAKE_NONCE_SEED,
+            &stake_manager.key().to_bytes(),
+            &user.key().to_bytes(),
+        ],
+        bump,
+    )]
+    pub unstake_nonce_account: Box<Account<'info, UnstakeNonceAccount>>,
+
     #[account(
         init,
         space = 8 + std::mem::size_of::<UnstakeAccount>(),
         payer = rent_payer,
         rent_exempt = enforce,
+        seeds = [
+            helper::UNSTAKE_ACCOUNT_SEED,
+            &stake_manager.key().to_bytes(),
+            &user.key().to_bytes(),
+            &unstake_nonce_account.nonce.to_le_bytes(),
+        ],
+        bump,
     )]
     pub unstake_account: Box<Account<'info, UnstakeAccount>>,
 
@@ -45,54 +65,53 @@ pub struct EventUnstake {
     pub era: u64,
     pub staker: Pubkey,
     pub unstake_account: Pubkey,
+    pub nonce: u64,
     pub unstake_amount: u64,
     pub staking_token_amount: u64,
     pub stake_manager: Pubkey,
 }
 
-impl<'info> Unstake<'info> {
-    pub fn process(&mut self, unstake_amount: u64) -> Result<()> {
-        require_gt!(unstake_amount, 0, Errors::UnstakeAmountIsZero);
+pub struct UnstakeArgs {
+    pub unstake_amount: u64,
+    pub min_staking_out: u64,
+    pub expected_era: Option<u64>,
+    pub deadline: Option<i64>,
+    pub unstake_account_bump: u8,
+    pub ticket_mint: Pubkey,
+}
 
-        require_gte!(
-            self.user_lsd_token_account.amount,
-            unstake_amount,
-            Errors::BalanceNotEnough
-        );
-
-        let staking_token_amount = self
-            .stake_manager
-            .calc_staking_token_amount(unstake_amount)?;
-        require_gt!(staking_token_amount, 0, Errors::UnstakeAccountAmountZero);
-
-        self.stake_manager.era_unbond += staking_token_amount;
-        self.stake_manager.active -= staking_token_amount;
-
-        // burn lsd token
-        burn(
-            CpiContext::new(
-                self.token_program.to_account_info(),
-                Burn {
-                    mint: self.lsd_token_mint.to_account_info(),
-                    from: self.user_lsd_token_account.to_account_info(),
-                    authority: self.user.to_account_info(),
-                },
-            ),
-            unstake_amount,
+impl<'info> Unstake<'info> {
+    pub fn process(
+        &mut self,
+        unstake_amount: u64,
+        min_staking_out: u64,
+        expected_era: Option<u64>,
+        deadline: Option<i64>,
+        unstake_account_bump: u8,
+    ) -> Result<()> {
+        let (nonce, staking_token_amount) = process_unstake(
+            &mut self.stake_manager,
+            &self.user,
+            &self.lsd_token_mint,
+            &self.user_lsd_token_account,
+            &mut self.unstake_nonce_account,
+            &mut self.unstake_account,
+            &self.token_program,
+            UnstakeArgs {
+                unstake_amount,
+                min_staking_out,
+                expected_era,
+                deadline,
+                unstake_account_bump,
+                ticket_mint: Pubkey::default(),
+            },
         )?;
 
-        self.unstake_account.set_inner(UnstakeAccount {
-            stake_manager: self.stake_manager.key(),
-            user: self.user.key(),
-            amount: staking_token_amount,
-            withdrawable_era: self.stake_manager.latest_era + self.stake_manager.unbonding_duration,
-            _reserved: [0u8; 128],
-        });
-
         emit!(EventUnstake {
             era: self.stake_manager.latest_era,
             staker: self.user.key(),
             unstake_account: self.unstake_account.key(),
+            nonce,
             unstake_amount,
             staking_token_amount,
             stake_manager: self.stake_manager.key(),
@@ -101,3 +120,71 @@ impl<'info> Unstake<'info> {
         Ok(())
     }
 }
+
+// burns the lsd tokens into a new unstake account, returns its nonce and staking token amount
+#[allow(clippy::too_many_arguments)]
+pub fn process_unstake<'info>(
+    stake_manager: &mut Account<'info, StakeManager>,
+    user: &Signer<'info>,
+    lsd_token_mint: &InterfaceAccount<'info, Mint>,
+    user_lsd_token_account: &InterfaceAccount<'info, TokenAccount>,
+    unstake_nonce_account: &mut Account<'info, UnstakeNonceAccount>,
+    unstake_account: &mut Account<'info, UnstakeAccount>,
+    token_program: &Interface<'info, TokenInterface>,
+    args: UnstakeArgs,
+) -> Result<(u64, u64)> {
+    stake_manager.check_not_paused(helper::PAUSE_UNSTAKE)?;
+
+    require_gt!(args.unstake_amount, 0, Errors::UnstakeAmountIsZero);
+    stake_manager.check_era_and_deadline(args.expected_era, args.deadline)?;
+
+    require_gte!(
+        user_lsd_token_account.amount,
+        args.unstake_amount,
+        Errors::BalanceNotEnough
+    );
+
+    let staking_token_amount = stake_manager.calc_staking_token_amount(args.unstake_amount)?;
+    require_gt!(staking_token_amount, 0, Errors::UnstakeAccountAmountZero);
+    require_gte!(
+        staking_token_amount,
+        args.min_staking_out,
+        Errors::StakingTokenAmountTooLow
+    );
+
+    stake_manager.era_unbond += staking_token_amount;
+    stake_manager.active -= staking_token_amount;
+
+    // burn lsd token
+    burn(
+        CpiContext::new(
+            token_program.to_account_info(),
+            Burn {
+                mint: lsd_token_mint.to_account_info(),
+                from: user_lsd_token_account.to_account_info(),
+                authority: user.to_account_info(),
+            },
+        ),
+        args.unstake_amount,
+    )?;
+
+    let nonce = unstake_nonce_account.nonce;
+    unstake_nonce_account.stake_manager = stake_manager.key();
+    unstake_nonce_account.user = user.key();
+    unstake_nonce_account.nonce = nonce + 1;
+    stake_manager.pending_unstake_amount += staking_token_amount;
+
+    unstake_account.set_inner(UnstakeAccount {
+        stake_manager: stake_manager.key(),
+        user: user.key(),
+        amount: staking_token_amount,
+        withdrawable_era: stake_manager.latest_era + stake_manager.unbonding_duration,
+        era: stake_manager.latest_era,
+        nonce,
+        bump: args.unstake_account_bump,
+        ticket_mint: args.ticket_mint,
+        _reserved: [0u8; 79],
+    });
+
+    Ok((nonce, staking_token_amount))
+}
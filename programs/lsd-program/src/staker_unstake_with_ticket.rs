@@ -0,0 +1,236 @@
+use crate::{
+    helper, process_unstake, Errors, StakeManager, UnstakeAccount, UnstakeArgs, UnstakeNonceAccount,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::metadata::Metadata;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use mpl_token_metadata::{
+    instructions::CreateV1Cpi, instructions::CreateV1CpiAccounts,
+    instructions::CreateV1InstructionArgs, instructions::MintV1Cpi,
+    instructions::MintV1CpiAccounts, instructions::MintV1InstructionArgs, types::PrintSupply,
+    types::TokenStandard,
+};
+
+#[derive(Accounts)]
+pub struct UnstakeWithTicket<'info> {
+    pub user: Signer<'info>,
+
+    #[account(mut)]
+    pub rent_payer: Signer<'info>,
+
+    #[account(mut)]
+    pub stake_manager: Box<Account<'info, StakeManager>>,
+
+    #[account(
+        mut,
+        address = stake_manager.lsd_token_mint @Errors::LsdTokenMintAccountNotMatch,
+    )]
+    pub lsd_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = lsd_token_mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub user_lsd_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        space = 8 + std::mem::size_of::<UnstakeNonceAccount>(),
+        payer = rent_payer,
+        seeds = [
+            helper::UNSTAKE_NONCE_SEED,
+            &stake_manager.key().to_bytes(),
+            &user.key().to_bytes(),
+        ],
+        bump,
+    )]
+    pub unstake_nonce_account: Box<Account<'info, UnstakeNonceAccount>>,
+
+    #[account(
+        init,
+        space = 8 + std::mem::size_of::<UnstakeAccount>(),
+        payer = rent_payer,
+        rent_exempt = enforce,
+        seeds = [
+            helper::UNSTAKE_ACCOUNT_SEED,
+            &stake_manager.key().to_bytes(),
+            &user.key().to_bytes(),
+            &unstake_nonce_account.nonce.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub unstake_account: Box<Account<'info, UnstakeAccount>>,
+
+    #[account(
+        init,
+        payer = rent_payer,
+        mint::decimals = 0,
+        mint::authority = stake_manager,
+        mint::freeze_authority = stake_manager,
+        seeds = [
+            helper::TICKET_MINT_SEED,
+            &unstake_account.key().to_bytes(),
+        ],
+        bump,
+    )]
+    pub ticket_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = rent_payer,
+        associated_token::mint = ticket_mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub user_ticket_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: Validate address by deriving pda
+    #[account(
+         mut,
+         seeds = [b"metadata", metadata_program.key().as_ref(), ticket_mint.key().as_ref()],
+         bump,
+         seeds::program = metadata_program.key(),
+     )]
+    pub ticket_metadata_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validate address by deriving pda
+    #[account(
+         mut,
+         seeds = [b"metadata", metadata_program.key().as_ref(), ticket_mint.key().as_ref(), b"edition"],
+         bump,
+         seeds::program = metadata_program.key(),
+     )]
+    pub ticket_master_edition_account: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub metadata_program: Program<'info, Metadata>,
+    pub system_program: Program<'info, System>,
+    /// CHECK:
+    pub sysvar_instruction: UncheckedAccount<'info>,
+}
+
+#[event]
+pub struct EventUnstakeWithTicket {
+    pub era: u64,
+    pub staker: Pubkey,
+    pub unstake_account: Pubkey,
+    pub nonce: u64,
+    pub ticket_mint: Pubkey,
+    pub unstake_amount: u64,
+    pub staking_token_amount: u64,
+    pub stake_manager: Pubkey,
+}
+
+impl<'info> UnstakeWithTicket<'info> {
+    pub fn process(
+        &mut self,
+        unstake_amount: u64,
+        min_staking_out: u64,
+        expected_era: Option<u64>,
+        deadline: Option<i64>,
+        unstake_account_bump: u8,
+    ) -> Result<()> {
+        let (nonce, staking_token_amount) = process_unstake(
+            &mut self.stake_manager,
+            &self.user,
+            &self.lsd_token_mint,
+            &self.user_lsd_token_account,
+            &mut self.unstake_nonce_account,
+            &mut self.unstake_account,
+            &self.token_program,
+            UnstakeArgs {
+                unstake_amount,
+                min_staking_out,
+                expected_era,
+                deadline,
+                unstake_account_bump,
+                ticket_mint: self.ticket_mint.key(),
+            },
+        )?;
+
+        let stake_manager = &self.stake_manager;
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            helper::STAKE_MANAGER_SEED,
+            &stake_manager.creator.to_bytes(),
+            &[stake_manager.index],
+            &[stake_manager.pool_seed_bump],
+        ]];
+
+        // the master edition takes over the mint authority, capping the supply at one
+        CreateV1Cpi::new(
+            &self.metadata_program.to_account_info(),
+            CreateV1CpiAccounts {
+                metadata: &self.ticket_metadata_account.to_account_info(),
+                master_edition: Some(&self.ticket_master_edition_account.to_account_info()),
+                mint: (&self.ticket_mint.to_account_info(), false),
+                authority: &stake_manager.to_account_info(),
+                payer: &self.rent_payer.to_account_info(),
+                update_authority: (&stake_manager.to_account_info(), false),
+                system_program: &self.system_program.to_account_info(),
+                sysvar_instructions: &self.sysvar_instruction.to_account_info(),
+                spl_token_program: &self.token_program.to_account_info(),
+            },
+            CreateV1InstructionArgs {
+                name: helper::TICKET_NAME.to_string(),
+                symbol: helper::TICKET_SYMBOL.to_string(),
+                uri: String::new(),
+                seller_fee_basis_points: 0,
+                creators: None,
+                primary_sale_happened: false,
+                is_mutable: false,
+                token_standard: TokenStandard::NonFungible,
+                collection: None,
+                uses: None,
+                collection_details: None,
+                rule_set: None,
+                decimals: Some(0),
+                print_supply: Some(PrintSupply::Zero),
+            },
+        )
+        .invoke_signed(signer_seeds)?;
+
+        // mint the withdrawal ticket, whoever holds it can withdraw
+        MintV1Cpi::new(
+            &self.metadata_program.to_account_info(),
+            MintV1CpiAccounts {
+                token: &self.user_ticket_token_account.to_account_info(),
+                token_owner: Some(&self.user.to_account_info()),
+                metadata: &self.ticket_metadata_account.to_account_info(),
+                master_edition: Some(&self.ticket_master_edition_account.to_account_info()),
+                token_record: None,
+                mint: &self.ticket_mint.to_account_info(),
+                authority: &stake_manager.to_account_info(),
+                delegate_record: None,
+                payer: &self.rent_payer.to_account_info(),
+                system_program: &self.system_program.to_account_info(),
+                sysvar_instructions: &self.sysvar_instruction.to_account_info(),
+                spl_token_program: &self.token_program.to_account_info(),
+                spl_ata_program: &self.associated_token_program.to_account_info(),
+                authorization_rules_program: None,
+                authorization_rules: None,
+            },
+            MintV1InstructionArgs {
+                amount: 1,
+                authorization_data: None,
+            },
+        )
+        .invoke_signed(signer_seeds)?;
+
+        emit!(EventUnstakeWithTicket {
+            era: stake_manager.latest_era,
+            staker: self.user.key(),
+            unstake_account: self.unstake_account.key(),
+            nonce,
+            ticket_mint: self.ticket_mint.key(),
+            unstake_amount,
+            staking_token_amount,
+            stake_manager: stake_manager.key(),
+        });
+
+        Ok(())
+    }
+}
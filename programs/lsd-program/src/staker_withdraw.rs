@@ -22,6 +22,14 @@ pub struct Withdraw<'info> {
         mut,
         close = rent_payer,
         has_one = stake_manager @Errors::InvalidUnstakeAccount,
+        constraint = unstake_account.ticket_mint == Pubkey::default() @Errors::UnstakeAccountHasTicket,
+        seeds = [
+            helper::UNSTAKE_ACCOUNT_SEED,
+            &stake_manager.key().to_bytes(),
+            &unstake_account.user.to_bytes(),
+            &unstake_account.nonce.to_le_bytes(),
+        ],
+        bump = unstake_account.bump,
     )]
     pub unstake_account: Account<'info, UnstakeAccount>,
 
@@ -63,45 +71,88 @@ pub struct EventWithdraw {
 
 impl<'info> Withdraw<'info> {
     pub fn process(&mut self) -> Result<()> {
-        require_gt!(
-            self.unstake_account.amount,
-            0,
-            Errors::UnstakeAccountAmountZero
-        );
-        require_gte!(
-            self.stake_manager.latest_era,
-            self.unstake_account.withdrawable_era,
-            Errors::UnstakeAccountNotWithdrawable
-        );
-
-        let withdraw_amount = self.unstake_account.amount;
-
-        require_gte!(
-            self.stake_manager_staking_token_account.amount,
-            withdraw_amount,
-            Errors::PoolBalanceNotEnough
-        );
-
-        self.unstake_account.amount = 0;
-
-        transfer_checked(
-            CpiContext::new_with_signer(
-                self.token_program.to_account_info(),
-                TransferChecked {
-                    from: self.stake_manager_staking_token_account.to_account_info(),
-                    mint: self.staking_token_mint.to_account_info(),
-                    to: self.user_staking_token_account.to_account_info(),
-                    authority: self.stake_manager.to_account_info(),
-                },
-                &[&[
-                    helper::STAKE_MANAGER_SEED,
-                    &self.stake_manager.creator.to_bytes(),
-                    &[self.stake_manager.index],
-                    &[self.stake_manager.pool_seed_bump],
-                ]],
-            ),
+        let withdraw_amount = withdraw(
+            &mut self.stake_manager,
+            &mut self.unstake_account,
+            &self.staking_token_mint,
+            &self.user_staking_token_account,
+            &self.stake_manager_staking_token_account,
+            &self.token_program,
+        )?;
+        self.stake_manager.pending_unstake_amount -= withdraw_amount;
+
+        emit!(EventWithdraw {
+            era: self.stake_manager.latest_era,
+            user: self.user.key(),
+            unstake_account: self.unstake_account.key(),
             withdraw_amount,
-            self.staking_token_mint.decimals,
+            stake_manager: self.stake_manager.key()
+        });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct WithdrawLegacy<'info> {
+    pub user: Signer<'info>,
+
+    #[account(mut)]
+    pub rent_payer: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = stake_manager.legacy_unstake_accounts @Errors::NoLegacyUnstakeAccounts,
+    )]
+    pub stake_manager: Box<Account<'info, StakeManager>>,
+
+    // keypair unstake accounts created before they were derived from the unstake nonce
+    #[account(
+        mut,
+        close = rent_payer,
+        has_one = stake_manager @Errors::InvalidUnstakeAccount,
+        has_one = user @Errors::UnstakeUserNotMatch,
+        constraint = unstake_account.ticket_mint == Pubkey::default() @Errors::UnstakeAccountHasTicket,
+        constraint = !unstake_account.is_pda(unstake_account.key()) @Errors::InvalidUnstakeAccount,
+    )]
+    pub unstake_account: Account<'info, UnstakeAccount>,
+
+    #[account(
+        address = stake_manager.staking_token_mint @Errors::StakingTokenMintAccountNotMatch
+    )]
+    pub staking_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer = rent_payer,
+        associated_token::mint = staking_token_mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub user_staking_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = staking_token_mint,
+        associated_token::authority = stake_manager,
+        associated_token::token_program = token_program,
+    )]
+    pub stake_manager_staking_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> WithdrawLegacy<'info> {
+    // keypair unstake accounts are not part of pending_unstake_amount
+    pub fn process(&mut self) -> Result<()> {
+        let withdraw_amount = withdraw(
+            &mut self.stake_manager,
+            &mut self.unstake_account,
+            &self.staking_token_mint,
+            &self.user_staking_token_account,
+            &self.stake_manager_staking_token_account,
+            &self.token_program,
         )?;
 
         emit!(EventWithdraw {
@@ -114,3 +165,53 @@ impl<'info> Withdraw<'info> {
         Ok(())
     }
 }
+
+fn withdraw<'info>(
+    stake_manager: &mut Account<'info, StakeManager>,
+    unstake_account: &mut Account<'info, UnstakeAccount>,
+    staking_token_mint: &InterfaceAccount<'info, Mint>,
+    user_staking_token_account: &InterfaceAccount<'info, TokenAccount>,
+    stake_manager_staking_token_account: &InterfaceAccount<'info, TokenAccount>,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<u64> {
+    stake_manager.check_not_paused(helper::PAUSE_WITHDRAW)?;
+
+    require_gt!(unstake_account.amount, 0, Errors::UnstakeAccountAmountZero);
+    require_gte!(
+        stake_manager.latest_era,
+        stake_manager.calc_withdrawable_era(unstake_account),
+        Errors::UnstakeAccountNotWithdrawable
+    );
+
+    let withdraw_amount = unstake_account.amount;
+
+    require_gte!(
+        stake_manager_staking_token_account.amount,
+        withdraw_amount,
+        Errors::PoolBalanceNotEnough
+    );
+
+    unstake_account.amount = 0;
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: stake_manager_staking_token_account.to_account_info(),
+                mint: staking_token_mint.to_account_info(),
+                to: user_staking_token_account.to_account_info(),
+                authority: stake_manager.to_account_info(),
+            },
+            &[&[
+                helper::STAKE_MANAGER_SEED,
+                &stake_manager.creator.to_bytes(),
+                &[stake_manager.index],
+                &[stake_manager.pool_seed_bump],
+            ]],
+        ),
+        withdraw_amount,
+        staking_token_mint.decimals,
+    )?;
+
+    Ok(withdraw_amount)
+}
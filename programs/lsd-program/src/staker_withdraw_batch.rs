@@ -0,0 +1,161 @@
+use crate::{helper, Errors, StakeManager, UnstakeAccount};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+#[derive(Accounts)]
+pub struct WithdrawBatch<'info> {
+    pub user: Signer<'info>,
+
+    #[account(mut)]
+    pub rent_payer: Signer<'info>,
+
+    #[account(mut)]
+    pub stake_manager: Box<Account<'info, StakeManager>>,
+
+    #[account(
+        address = stake_manager.staking_token_mint @Errors::StakingTokenMintAccountNotMatch
+    )]
+    pub staking_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer = rent_payer,
+        associated_token::mint = staking_token_mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub user_staking_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = staking_token_mint,
+        associated_token::authority = stake_manager,
+        associated_token::token_program = token_program,
+    )]
+    pub stake_manager_staking_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct EventWithdrawBatch {
+    pub era: u64,
+    pub user: Pubkey,
+    pub unstake_accounts: Vec<Pubkey>,
+    pub withdraw_amount: u64,
+    pub stake_manager: Pubkey,
+}
+
+impl<'info> WithdrawBatch<'info> {
+    pub fn process(&mut self, unstake_account_infos: &'info [AccountInfo<'info>]) -> Result<()> {
+        self.stake_manager
+            .check_not_paused(helper::PAUSE_WITHDRAW)?;
+
+        require!(
+            !unstake_account_infos.is_empty(),
+            Errors::RemainingAccountsNotMatch
+        );
+
+        let mut withdraw_amount: u64 = 0;
+        let mut unstake_accounts = Vec::with_capacity(unstake_account_infos.len());
+
+        for unstake_account_info in unstake_account_infos.iter() {
+            require!(
+                unstake_account_info.is_writable,
+                Errors::InvalidUnstakeAccount
+            );
+
+            let unstake_account = Account::<UnstakeAccount>::try_from(unstake_account_info)?;
+
+            require_keys_eq!(
+                unstake_account.stake_manager,
+                self.stake_manager.key(),
+                Errors::InvalidUnstakeAccount
+            );
+            require_keys_eq!(
+                unstake_account.user,
+                self.user.key(),
+                Errors::UnstakeUserNotMatch
+            );
+            require_keys_eq!(
+                unstake_account.ticket_mint,
+                Pubkey::default(),
+                Errors::UnstakeAccountHasTicket
+            );
+
+            let unstake_account_address = Pubkey::create_program_address(
+                &[
+                    helper::UNSTAKE_ACCOUNT_SEED,
+                    &self.stake_manager.key().to_bytes(),
+                    &unstake_account.user.to_bytes(),
+                    &unstake_account.nonce.to_le_bytes(),
+                    &[unstake_account.bump],
+                ],
+                &crate::ID,
+            )
+            .map_err(|_| error!(Errors::InvalidUnstakeAccount))?;
+            require_keys_eq!(
+                unstake_account_address,
+                unstake_account.key(),
+                Errors::InvalidUnstakeAccount
+            );
+
+            require_gt!(unstake_account.amount, 0, Errors::UnstakeAccountAmountZero);
+            require_gte!(
+                self.stake_manager.latest_era,
+                self.stake_manager.calc_withdrawable_era(&unstake_account),
+                Errors::UnstakeAccountNotWithdrawable
+            );
+
+            withdraw_amount = withdraw_amount
+                .checked_add(unstake_account.amount)
+                .ok_or(error!(Errors::CalculationFail))?;
+            unstake_accounts.push(unstake_account.key());
+
+            // closing here also rejects a ticket passed twice
+            unstake_account.close(self.rent_payer.to_account_info())?;
+        }
+
+        require_gte!(
+            self.stake_manager_staking_token_account.amount,
+            withdraw_amount,
+            Errors::PoolBalanceNotEnough
+        );
+
+        self.stake_manager.pending_unstake_amount -= withdraw_amount;
+
+        transfer_checked(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: self.stake_manager_staking_token_account.to_account_info(),
+                    mint: self.staking_token_mint.to_account_info(),
+                    to: self.user_staking_token_account.to_account_info(),
+                    authority: self.stake_manager.to_account_info(),
+                },
+                &[&[
+                    helper::STAKE_MANAGER_SEED,
+                    &self.stake_manager.creator.to_bytes(),
+                    &[self.stake_manager.index],
+                    &[self.stake_manager.pool_seed_bump],
+                ]],
+            ),
+            withdraw_amount,
+            self.staking_token_mint.decimals,
+        )?;
+
+        emit!(EventWithdrawBatch {
+            era: self.stake_manager.latest_era,
+            user: self.user.key(),
+            unstake_accounts,
+            withdraw_amount,
+            stake_manager: self.stake_manager.key()
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,157 @@
+use crate::{helper, Errors, StakeManager, UnstakeAccount};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{
+    burn, transfer_checked, Burn, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+#[derive(Accounts)]
+pub struct WithdrawTicket<'info> {
+    pub holder: Signer<'info>,
+
+    #[account(mut)]
+    pub rent_payer: Signer<'info>,
+
+    #[account(mut)]
+    pub stake_manager: Box<Account<'info, StakeManager>>,
+
+    #[account(
+        mut,
+        close = rent_payer,
+        has_one = stake_manager @Errors::InvalidUnstakeAccount,
+        has_one = ticket_mint @Errors::TicketMintNotMatch,
+        seeds = [
+            helper::UNSTAKE_ACCOUNT_SEED,
+            &stake_manager.key().to_bytes(),
+            &unstake_account.user.to_bytes(),
+            &unstake_account.nonce.to_le_bytes(),
+        ],
+        bump = unstake_account.bump,
+    )]
+    pub unstake_account: Account<'info, UnstakeAccount>,
+
+    #[account(mut)]
+    pub ticket_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = ticket_mint,
+        token::authority = holder,
+        token::token_program = token_program,
+    )]
+    pub holder_ticket_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        address = stake_manager.staking_token_mint @Errors::StakingTokenMintAccountNotMatch
+    )]
+    pub staking_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer = rent_payer,
+        associated_token::mint = staking_token_mint,
+        associated_token::authority = holder,
+        associated_token::token_program = token_program,
+    )]
+    pub holder_staking_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = staking_token_mint,
+        associated_token::authority = stake_manager,
+        associated_token::token_program = token_program,
+    )]
+    pub stake_manager_staking_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct EventWithdrawTicket {
+    pub era: u64,
+    pub holder: Pubkey,
+    pub unstake_account: Pubkey,
+    pub ticket_mint: Pubkey,
+    pub withdraw_amount: u64,
+    pub stake_manager: Pubkey,
+}
+
+impl<'info> WithdrawTicket<'info> {
+    pub fn process(&mut self) -> Result<()> {
+        self.stake_manager
+            .check_not_paused(helper::PAUSE_WITHDRAW)?;
+
+        require_gt!(
+            self.unstake_account.amount,
+            0,
+            Errors::UnstakeAccountAmountZero
+        );
+        require_gte!(
+            self.stake_manager.latest_era,
+            self.stake_manager
+                .calc_withdrawable_era(&self.unstake_account),
+            Errors::UnstakeAccountNotWithdrawable
+        );
+        require_gte!(
+            self.holder_ticket_token_account.amount,
+            1,
+            Errors::BalanceNotEnough
+        );
+
+        let withdraw_amount = self.unstake_account.amount;
+
+        require_gte!(
+            self.stake_manager_staking_token_account.amount,
+            withdraw_amount,
+            Errors::PoolBalanceNotEnough
+        );
+
+        self.unstake_account.amount = 0;
+        self.stake_manager.pending_unstake_amount -= withdraw_amount;
+
+        // burn the withdrawal ticket
+        burn(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                Burn {
+                    mint: self.ticket_mint.to_account_info(),
+                    from: self.holder_ticket_token_account.to_account_info(),
+                    authority: self.holder.to_account_info(),
+                },
+            ),
+            1,
+        )?;
+
+        transfer_checked(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: self.stake_manager_staking_token_account.to_account_info(),
+                    mint: self.staking_token_mint.to_account_info(),
+                    to: self.holder_staking_token_account.to_account_info(),
+                    authority: self.stake_manager.to_account_info(),
+                },
+                &[&[
+                    helper::STAKE_MANAGER_SEED,
+                    &self.stake_manager.creator.to_bytes(),
+                    &[self.stake_manager.index],
+                    &[self.stake_manager.pool_seed_bump],
+                ]],
+            ),
+            withdraw_amount,
+            self.staking_token_mint.decimals,
+        )?;
+
+        emit!(EventWithdrawTicket {
+            era: self.stake_manager.latest_era,
+            holder: self.holder.key(),
+            unstake_account: self.unstake_account.key(),
+            ticket_mint: self.ticket_mint.key(),
+            withdraw_amount,
+            stake_manager: self.stake_manager.key()
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,404 @@
+use crate::{helper, EraStatus, Errors, StakeManager};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use staking_program;
+
+#[derive(Accounts)]
+pub struct StartStakingPoolMigration<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = admin @ Errors::AdminNotMatch
+    )]
+    pub stake_manager: Box<Account<'info, StakeManager>>,
+
+    #[account(
+        constraint = new_staking_pool.key() != stake_manager.staking_pool @Errors::ParamsNotMatch,
+        constraint = new_staking_pool.token_mint == stake_manager.staking_token_mint @Errors::StakingTokenMintAccountNotMatch,
+    )]
+    pub new_staking_pool: Box<Account<'info, staking_program::StakingPool>>,
+}
+
+#[event]
+pub struct EventStartStakingPoolMigration {
+    pub stake_manager: Pubkey,
+    pub admin: Pubkey,
+    pub era: u64,
+    pub old_staking_pool: Pubkey,
+    pub new_staking_pool: Pubkey,
+    pub new_staking_program: Pubkey,
+}
+
+impl<'info> StartStakingPoolMigration<'info> {
+    pub fn process(&mut self) -> Result<()> {
+        require!(
+            !self.stake_manager.is_migrating(),
+            Errors::StakingPoolMigrating
+        );
+        require!(!self.stake_manager.wind_down, Errors::WindDown);
+        require!(
+            self.stake_manager.era_status == EraStatus::ActiveUpdated,
+            Errors::EraStatusNotMatch
+        );
+
+        let new_staking_program = self.new_staking_pool.to_account_info().owner.key();
+        let new_unbonding_duration =
+            self.new_staking_pool.unbonding_seconds / self.stake_manager.era_seconds as u64 + 1;
+
+        self.stake_manager.migration_staking_pool = self.new_staking_pool.key();
+        self.stake_manager.migration_staking_program = new_staking_program;
+        self.stake_manager.migration_started_at = Clock::get()?.unix_timestamp;
+        self.stake_manager.migration_unbonding = 0;
+        self.stake_manager.migration_reserved = 0;
+        self.stake_manager.migration_bonded = false;
+        self.stake_manager.migration_pool_unbonding = 0;
+
+        // bonds go to the new staking pool from now on, unstakes may be paid from either pool
+        self.stake_manager.staking_min_stake_amount = self.new_staking_pool.min_stake_amount;
+        self.stake_manager.unbonding_duration = self
+            .stake_manager
+            .unbonding_duration
+            .max(new_unbonding_duration);
+
+        emit!(EventStartStakingPoolMigration {
+            stake_manager: self.stake_manager.key(),
+            admin: self.admin.key(),
+            era: self.stake_manager.latest_era,
+            old_staking_pool: self.stake_manager.staking_pool,
+            new_staking_pool: self.new_staking_pool.key(),
+            new_staking_program,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct MigrateUnbond<'info> {
+    #[account(mut)]
+    pub fee_and_rent_payer: Signer<'info>,
+
+    #[account(mut)]
+    pub stake_manager: Box<Account<'info, StakeManager>>,
+
+    #[account(
+        mut,
+        address = stake_manager.staking_pool @Errors::SpStakePoolNotMatch,
+    )]
+    pub staking_pool: Box<Account<'info, staking_program::StakingPool>>,
+
+    #[account(mut)]
+    pub staking_stake_account: Box<Account<'info, staking_program::StakeAccount>>,
+
+    #[account(mut)]
+    pub staking_unstake_account: Signer<'info>,
+
+    /// CHECK: staking_program
+    #[account(
+        address = stake_manager.staking_program @Errors::SpNotMatch
+    )]
+    pub staking_program: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct EventMigrateUnbond {
+    pub stake_manager: Pubkey,
+    pub era: u64,
+    pub staking_pool: Pubkey,
+    pub unbond_amount: u64,
+    pub migration_unbonding: u64,
+}
+
+impl<'info> MigrateUnbond<'info> {
+    pub fn process(&mut self) -> Result<()> {
+        self.stake_manager
+            .check_not_paused(helper::PAUSE_ERA_UNBOND)?;
+
+        require!(
+            self.stake_manager.is_migrating(),
+            Errors::StakingPoolNotMigrating
+        );
+        require!(
+            self.staking_stake_account.to_account_info().owner.key() == self.staking_program.key()
+                && self.staking_stake_account.user.key() == self.stake_manager.key()
+                && self.staking_stake_account.staking_pool.key() == self.staking_pool.key(),
+            Errors::SpStakeAccountNotMatch
+        );
+
+        // restaked rewards keep landing in the old stake account, so this can run every era
+        let unbond_amount = self.staking_stake_account.amount;
+        require_gt!(unbond_amount, 0, Errors::UnstakeAmountIsZero);
+
+        let cpi_accounts = staking_program::cpi::accounts::Unstake {
+            user: self.stake_manager.to_account_info(),
+            rent_payer: self.fee_and_rent_payer.to_account_info(),
+            staking_pool: self.staking_pool.to_account_info(),
+            stake_account: self.staking_stake_account.to_account_info(),
+            unstake_account: self.staking_unstake_account.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+        };
+
+        staking_program::cpi::unstake(
+            CpiContext::new_with_signer(
+                self.staking_program.to_account_info(),
+                cpi_accounts,
+                &[&[
+                    helper::STAKE_MANAGER_SEED,
+                    &self.stake_manager.creator.to_bytes(),
+                    &[self.stake_manager.index],
+                    &[self.stake_manager.pool_seed_bump],
+                ]],
+            ),
+            unbond_amount,
+        )?;
+
+        self.stake_manager.migration_unbonding += unbond_amount;
+
+        emit!(EventMigrateUnbond {
+            stake_manager: self.stake_manager.key(),
+            era: self.stake_manager.latest_era,
+            staking_pool: self.staking_pool.key(),
+            unbond_amount,
+            migration_unbonding: self.stake_manager.migration_unbonding,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct MigrateWithdraw<'info> {
+    #[account(mut)]
+    pub fee_and_rent_payer: Signer<'info>,
+
+    #[account(mut)]
+    pub stake_manager: Box<Account<'info, StakeManager>>,
+
+    #[account(
+        mut,
+        address = stake_manager.staking_pool @Errors::SpStakePoolNotMatch,
+    )]
+    pub staking_pool: Box<Account<'info, staking_program::StakingPool>>,
+
+    #[account(
+        address = stake_manager.staking_token_mint @Errors::StakingTokenMintAccountNotMatch
+    )]
+    pub staking_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = staking_token_mint,
+        associated_token::authority = stake_manager,
+        associated_token::token_program = token_program,
+    )]
+    pub stake_manager_staking_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub staking_pool_staking_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub staking_unstake_account: Box<Account<'info, staking_program::UnstakeAccount>>,
+
+    /// CHECK: staking_program
+    #[account(
+        address = stake_manager.staking_program @Errors::SpNotMatch
+    )]
+    pub staking_program: AccountInfo<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct EventMigrateWithdraw {
+    pub stake_manager: Pubkey,
+    pub era: u64,
+    pub staking_pool: Pubkey,
+    pub withdraw_amount: u64,
+    pub rebond_amount: u64,
+    pub old_migration_unbonding: u64,
+    pub new_migration_unbonding: u64,
+    pub old_migration_reserved: u64,
+    pub new_migration_reserved: u64,
+}
+
+impl<'info> MigrateWithdraw<'info> {
+    pub fn process(&mut self) -> Result<()> {
+        self.stake_manager
+            .check_not_paused(helper::PAUSE_ERA_WITHDRAW)?;
+
+        require!(
+            self.stake_manager.is_migrating(),
+            Errors::StakingPoolNotMigrating
+        );
+
+        let timestamp = Clock::get()?.unix_timestamp as u64;
+        require!(
+            self.staking_unstake_account.withdrawable_timestamp <= timestamp,
+            Errors::UnstakeAccountNotWithdrawable
+        );
+        // unstake accounts created before the migration go through era_withdraw
+        require_gte!(
+            self.staking_unstake_account.withdrawable_timestamp,
+            self.stake_manager
+                .calc_migration_withdrawable_timestamp(self.staking_pool.unbonding_seconds),
+            Errors::ParamsNotMatch
+        );
+
+        let balance_before = self.stake_manager_staking_token_account.amount;
+
+        let cpi_accounts = staking_program::cpi::accounts::Withdraw {
+            user: self.stake_manager.to_account_info(),
+            rent_payer: self.fee_and_rent_payer.to_account_info(),
+            staking_pool: self.staking_pool.to_account_info(),
+            token_mint: self.staking_token_mint.to_account_info(),
+            user_token_account: self.stake_manager_staking_token_account.to_account_info(),
+            pool_token_account: self.staking_pool_staking_token_account.to_account_info(),
+            unstake_account: self.staking_unstake_account.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+            associated_token_program: self.associated_token_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+        };
+
+        staking_program::cpi::withdraw(CpiContext::new_with_signer(
+            self.staking_program.to_account_info(),
+            cpi_accounts,
+            &[&[
+                helper::STAKE_MANAGER_SEED,
+                &self.stake_manager.creator.to_bytes(),
+                &[self.stake_manager.index],
+                &[self.stake_manager.pool_seed_bump],
+            ]],
+        ))?;
+
+        self.stake_manager_staking_token_account.reload()?;
+        let withdraw_amount = self.stake_manager_staking_token_account.amount - balance_before;
+
+        require_gte!(
+            self.stake_manager.migration_unbonding,
+            withdraw_amount,
+            Errors::CalculationFail
+        );
+
+        // what is owed to unstakes stays idle, the rest is rebonded into the new staking pool
+        let reserved_amount = withdraw_amount.min(self.stake_manager.migration_reserved);
+        let rebond_amount = withdraw_amount - reserved_amount;
+
+        let old_migration_unbonding = self.stake_manager.migration_unbonding;
+        let old_migration_reserved = self.stake_manager.migration_reserved;
+
+        self.stake_manager.migration_unbonding -= withdraw_amount;
+        self.stake_manager.migration_reserved -= reserved_amount;
+        self.stake_manager.era_bond += rebond_amount;
+
+        emit!(EventMigrateWithdraw {
+            stake_manager: self.stake_manager.key(),
+            era: self.stake_manager.latest_era,
+            staking_pool: self.staking_pool.key(),
+            withdraw_amount,
+            rebond_amount,
+            old_migration_unbonding,
+            new_migration_unbonding: self.stake_manager.migration_unbonding,
+            old_migration_reserved,
+            new_migration_reserved: self.stake_manager.migration_reserved,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct FinishStakingPoolMigration<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = admin @ Errors::AdminNotMatch
+    )]
+    pub stake_manager: Box<Account<'info, StakeManager>>,
+
+    #[account(
+        address = stake_manager.staking_pool @Errors::SpStakePoolNotMatch,
+    )]
+    pub staking_pool: Box<Account<'info, staking_program::StakingPool>>,
+
+    pub staking_stake_account: Box<Account<'info, staking_program::StakeAccount>>,
+
+    #[account(
+        address = stake_manager.migration_staking_pool @Errors::SpStakePoolNotMatch,
+    )]
+    pub new_staking_pool: Box<Account<'info, staking_program::StakingPool>>,
+}
+
+#[event]
+pub struct EventFinishStakingPoolMigration {
+    pub stake_manager: Pubkey,
+    pub admin: Pubkey,
+    pub era: u64,
+    pub old_staking_pool: Pubkey,
+    pub new_staking_pool: Pubkey,
+    pub new_staking_program: Pubkey,
+}
+
+impl<'info> FinishStakingPoolMigration<'info> {
+    pub fn process(&mut self) -> Result<()> {
+        // any era status, an era_unbond waiting for the new staking pool can only go on after this
+        require!(
+            self.stake_manager.is_migrating(),
+            Errors::StakingPoolNotMigrating
+        );
+        require!(
+            self.staking_stake_account.to_account_info().owner.key()
+                == self.stake_manager.staking_program
+                && self.staking_stake_account.user.key() == self.stake_manager.key()
+                && self.staking_stake_account.staking_pool.key() == self.staking_pool.key(),
+            Errors::SpStakeAccountNotMatch
+        );
+
+        // the old position is fully drained and era_active can switch to the new stake account.
+        // unstake accounts created before the migration must be withdrawn by era_withdraw first,
+        // the timestamp covers the ones unbonded before unbonding was tracked.
+        let timestamp = Clock::get()?.unix_timestamp as u64;
+        require!(
+            self.stake_manager.migration_bonded
+                && self.staking_stake_account.amount == 0
+                && self.stake_manager.migration_unbonding == 0
+                && self.stake_manager.unbonding == 0
+                && timestamp
+                    >= self
+                        .stake_manager
+                        .calc_migration_withdrawable_timestamp(self.staking_pool.unbonding_seconds),
+            Errors::StakingPoolMigrating
+        );
+
+        let old_staking_pool = self.stake_manager.staking_pool;
+        let new_staking_program = self.stake_manager.migration_staking_program;
+
+        self.stake_manager.staking_pool = self.new_staking_pool.key();
+        self.stake_manager.staking_program = new_staking_program;
+        self.stake_manager.staking_min_stake_amount = self.new_staking_pool.min_stake_amount;
+        self.stake_manager.unbonding_duration =
+            self.new_staking_pool.unbonding_seconds / self.stake_manager.era_seconds as u64 + 1;
+
+        self.stake_manager.migration_staking_pool = Pubkey::default();
+        self.stake_manager.migration_staking_program = Pubkey::default();
+        self.stake_manager.migration_started_at = 0;
+        self.stake_manager.migration_reserved = 0;
+        self.stake_manager.migration_bonded = false;
+        // unbonds from the new staking pool are withdrawn by era_withdraw from now on
+        self.stake_manager.unbonding = self.stake_manager.migration_pool_unbonding;
+        self.stake_manager.migration_pool_unbonding = 0;
+
+        emit!(EventFinishStakingPoolMigration {
+            stake_manager: self.stake_manager.key(),
+            admin: self.admin.key(),
+            era: self.stake_manager.latest_era,
+            old_staking_pool,
+            new_staking_pool: self.new_staking_pool.key(),
+            new_staking_program,
+        });
+        Ok(())
+    }
+}
@@ -1,5 +1,5 @@
 pub use crate::errors::Errors;
-use crate::helper;
+use crate::{helper, ConfigStakeManagerParams};
 use anchor_lang::prelude::*;
 
 #[account]
@@ -38,6 +38,69 @@ pub struct StakeManager {
 
     pub era_rates: Vec<EraRate>,
 
+    // fields appended after era_rates read as zero from the reserved bytes of older accounts
+    pub instant_unstake_fee_commission: u64, // decimals 9
+    pub instant_unstake_fee_mode: InstantUnstakeFeeMode,
+    pub buffer_target_bps: u64, // decimals 4
+    pub buffer: u64,
+    pub paused: u32, // bitmap of helper::PAUSE_*
+
+    // roles, admin can act as any of them
+    pub operator: Pubkey,
+    pub pending_operator: Pubkey,
+    pub fee_manager: Pubkey,
+    pub pending_fee_manager: Pubkey,
+    pub pauser: Pubkey,
+    pub pending_pauser: Pubkey,
+    pub metadata_authority: Pubkey,
+    pub pending_metadata_authority: Pubkey,
+
+    // timelocked config
+    pub config_delay: i64, // seconds
+    pub pending_config: PendingConfig,
+
+    pub renounced: bool, // admin renounced, all configurable params are frozen
+
+    pub fee_recipient: Pubkey, // owner of the platform fee lsd token account
+    pub fee_splits: Vec<FeeSplit>, // remainder of the split goes to fee_recipient
+
+    // wind down, no new stakes and everything is unbonded from the staking pool
+    pub wind_down: bool,
+    pub wind_down_idle: u64, // holders' staking tokens already unbonded from the staking pool
+    pub pending_unstake_amount: u64, // owed to unstake accounts derived from the unstake nonce
+    pub unbonding: u64, // unbonded from staking_pool by era_unbond, not withdrawn by era_withdraw yet
+    pub legacy_unstake_accounts: bool, // keypair unstake accounts from before versioning may be open
+    pub legacy_withdrawable_era: u64, // every keypair unstake account is withdrawable from this era on
+
+    // staking pool migration, staking_pool is drained into migration_staking_pool
+    pub migration_staking_pool: Pubkey, // default when not migrating
+    pub migration_staking_program: Pubkey,
+    pub migration_started_at: i64,
+    pub migration_unbonding: u64, // unbonded from staking_pool during the migration, not withdrawn yet
+    pub migration_reserved: u64,  // part of migration_unbonding owed to unstakes
+    pub migration_bonded: bool,   // migration_staking_pool holds a stake account
+    pub migration_pool_unbonding: u64, // unbonded from migration_staking_pool by era_unbond
+
+    pub version: u8, // 0 for accounts created before versioning, see migrate_stake_manager
+
+    // staked into and unstaked from the staking pool since the latest era_new, see EraSnapshot
+    pub bonded_amount: u64,
+    pub unbonded_amount: u64,
+
+    // era_catch_up jumped from catch_up_era, whose unstakes were only unbonded catch_up_shift eras late.
+    // The skipped eras catch_up_era + 1 ..= catch_up_era + catch_up_shift have no EraSnapshot.
+    pub catch_up_era: u64,
+    pub catch_up_shift: u64,
+    pub catch_up_unbonding_duration: u64, // unbonding_duration when era_catch_up ran
+
+    // unstakes cancelled after netting, held until their unbond landed, see release_landed_unbonds
+    pub pending_rebond: u64,
+    pub pending_rebond_era: u64, // latest withdrawable era among them
+
+    // unbonded along with net unstakes to refill the buffer, credited to it once landed
+    pub buffer_unbonding: u64,
+    pub buffer_unbonding_era: u64,
+
     /// Reserved space for future upgrades. Do not use.
     pub _reserved: [u8; 256],
 }
@@ -48,6 +111,93 @@ pub struct EraRate {
     pub rate: u64,
 }
 
+#[derive(Clone, Debug, Default, AnchorSerialize, AnchorDeserialize)]
+pub struct FeeSplit {
+    pub recipient: Pubkey, // lsd token account
+    pub weight: u64,       // decimals 9
+}
+
+// fixed layout of proposed ConfigStakeManagerParams, set marks the fields that are proposed
+#[derive(Clone, Copy, Debug, Default, AnchorSerialize, AnchorDeserialize)]
+pub struct PendingConfig {
+    pub proposer: Pubkey, // default when nothing is pending
+    pub effective_at: i64,
+    pub set: u8, // bitmap of helper::CONFIG_*
+    pub min_stake_amount: u64,
+    pub platform_fee_commission: u64,
+    pub rate_change_limit: u64,
+    pub instant_unstake_fee_commission: u64,
+    pub instant_unstake_fee_mode: InstantUnstakeFeeMode,
+    pub buffer_target_bps: u64,
+    pub config_delay: i64,
+}
+
+impl PendingConfig {
+    pub fn new(params: &ConfigStakeManagerParams, proposer: Pubkey, effective_at: i64) -> Self {
+        let mut pending_config = PendingConfig {
+            proposer,
+            effective_at,
+            ..Default::default()
+        };
+
+        if let Some(min_stake_amount) = params.min_stake_amount {
+            pending_config.set |= helper::CONFIG_MIN_STAKE_AMOUNT;
+            pending_config.min_stake_amount = min_stake_amount;
+        }
+        if let Some(platform_fee_commission) = params.platform_fee_commission {
+            pending_config.set |= helper::CONFIG_PLATFORM_FEE_COMMISSION;
+            pending_config.platform_fee_commission = platform_fee_commission;
+        }
+        if let Some(rate_change_limit) = params.rate_change_limit {
+            pending_config.set |= helper::CONFIG_RATE_CHANGE_LIMIT;
+            pending_config.rate_change_limit = rate_change_limit;
+        }
+        if let Some(instant_unstake_fee_commission) = params.instant_unstake_fee_commission {
+            pending_config.set |= helper::CONFIG_INSTANT_UNSTAKE_FEE_COMMISSION;
+            pending_config.instant_unstake_fee_commission = instant_unstake_fee_commission;
+        }
+        if let Some(instant_unstake_fee_mode) = params.instant_unstake_fee_mode {
+            pending_config.set |= helper::CONFIG_INSTANT_UNSTAKE_FEE_MODE;
+            pending_config.instant_unstake_fee_mode = instant_unstake_fee_mode;
+        }
+        if let Some(buffer_target_bps) = params.buffer_target_bps {
+            pending_config.set |= helper::CONFIG_BUFFER_TARGET_BPS;
+            pending_config.buffer_target_bps = buffer_target_bps;
+        }
+        if let Some(config_delay) = params.config_delay {
+            pending_config.set |= helper::CONFIG_CONFIG_DELAY;
+            pending_config.config_delay = config_delay;
+        }
+
+        pending_config
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.proposer != Pubkey::default()
+    }
+
+    pub fn params(&self) -> ConfigStakeManagerParams {
+        ConfigStakeManagerParams {
+            min_stake_amount: (self.set & helper::CONFIG_MIN_STAKE_AMOUNT != 0)
+                .then_some(self.min_stake_amount),
+            platform_fee_commission: (self.set & helper::CONFIG_PLATFORM_FEE_COMMISSION != 0)
+                .then_some(self.platform_fee_commission),
+            rate_change_limit: (self.set & helper::CONFIG_RATE_CHANGE_LIMIT != 0)
+                .then_some(self.rate_change_limit),
+            instant_unstake_fee_commission: (self.set
+                & helper::CONFIG_INSTANT_UNSTAKE_FEE_COMMISSION
+                != 0)
+                .then_some(self.instant_unstake_fee_commission),
+            instant_unstake_fee_mode: (self.set & helper::CONFIG_INSTANT_UNSTAKE_FEE_MODE != 0)
+                .then_some(self.instant_unstake_fee_mode),
+            buffer_target_bps: (self.set & helper::CONFIG_BUFFER_TARGET_BPS != 0)
+                .then_some(self.buffer_target_bps),
+            config_delay: (self.set & helper::CONFIG_CONFIG_DELAY != 0)
+                .then_some(self.config_delay),
+        }
+    }
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
 pub enum EraStatus {
     EraUpdated,
@@ -56,7 +206,78 @@ pub enum EraStatus {
     ActiveUpdated,
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum InstantUnstakeFeeMode {
+    #[default]
+    LsdHolders,
+    Platform,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Role {
+    Operator,
+    FeeManager,
+    Pauser,
+    MetadataAuthority,
+}
+
 impl StakeManager {
+    // managers that never set fee_recipient keep paying the platform fee to the admin
+    pub fn fee_recipient_or_admin(&self) -> Pubkey {
+        if self.fee_recipient == Pubkey::default() {
+            self.admin
+        } else {
+            self.fee_recipient
+        }
+    }
+
+    pub fn role(&self, role: Role) -> Pubkey {
+        match role {
+            Role::Operator => self.operator,
+            Role::FeeManager => self.fee_manager,
+            Role::Pauser => self.pauser,
+            Role::MetadataAuthority => self.metadata_authority,
+        }
+    }
+
+    pub fn pending_role(&self, role: Role) -> Pubkey {
+        match role {
+            Role::Operator => self.pending_operator,
+            Role::FeeManager => self.pending_fee_manager,
+            Role::Pauser => self.pending_pauser,
+            Role::MetadataAuthority => self.pending_metadata_authority,
+        }
+    }
+
+    pub fn set_pending_role(&mut self, role: Role, pending: Pubkey) {
+        match role {
+            Role::Operator => self.pending_operator = pending,
+            Role::FeeManager => self.pending_fee_manager = pending,
+            Role::Pauser => self.pending_pauser = pending,
+            Role::MetadataAuthority => self.pending_metadata_authority = pending,
+        }
+    }
+
+    pub fn accept_role(&mut self, role: Role) {
+        let pending = self.pending_role(role);
+        match role {
+            Role::Operator => self.operator = pending,
+            Role::FeeManager => self.fee_manager = pending,
+            Role::Pauser => self.pauser = pending,
+            Role::MetadataAuthority => self.metadata_authority = pending,
+        }
+        self.set_pending_role(role, Pubkey::default());
+    }
+
+    pub fn check_role(&self, role: Role, authority: &Pubkey) -> Result<()> {
+        require!(
+            *authority == self.admin || *authority == self.role(role),
+            Errors::RoleNotMatch
+        );
+
+        Ok(())
+    }
+
     pub fn calc_lsd_token_amount(&self, staking_token_amount: u64) -> Result<u64> {
         u64::try_from(
             (staking_token_amount as u128) * (helper::CAL_BASE as u128) / (self.rate as u128),
@@ -76,6 +297,42 @@ impl StakeManager {
         .map_err(|_| error!(Errors::CalculationFail))
     }
 
+    pub fn calc_instant_unstake_fee(&self, lsd_token_amount: u64) -> Result<u64> {
+        u64::try_from(
+            (lsd_token_amount as u128) * (self.instant_unstake_fee_commission as u128)
+                / (helper::CAL_BASE as u128),
+        )
+        .map_err(|_| error!(Errors::CalculationFail))
+    }
+
+    // staking tokens deposited but not yet bonded, net of the exits already matched against them
+    pub fn calc_idle_deposit(&self) -> u64 {
+        let mut idle = self.era_bond;
+        if self.era_status != EraStatus::EraUpdated {
+            idle = (idle + self.pending_bond).saturating_sub(self.pending_unbond);
+        }
+        idle.saturating_sub(self.era_unbond)
+    }
+
+    pub fn calc_instant_unstake_liquidity(&self) -> u64 {
+        self.calc_idle_deposit() + self.buffer
+    }
+
+    pub fn calc_buffer_target(&self) -> Result<u64> {
+        if self.wind_down {
+            return Ok(0);
+        }
+        u64::try_from(
+            (self.active as u128) * (self.buffer_target_bps as u128) / (helper::BPS_BASE as u128),
+        )
+        .map_err(|_| error!(Errors::CalculationFail))
+    }
+
+    pub fn calc_fee_split_amount(&self, platform_fee: u64, weight: u64) -> Result<u64> {
+        u64::try_from((platform_fee as u128) * (weight as u128) / (helper::CAL_BASE as u128))
+            .map_err(|_| error!(Errors::CalculationFail))
+    }
+
     pub fn calc_rate(&self, staking_token_amount: u64, lsd_token_amount: u64) -> Result<u64> {
         if staking_token_amount == 0 || lsd_token_amount == 0 {
             return Ok(helper::CAL_BASE);
@@ -106,6 +363,108 @@ impl StakeManager {
         u64::try_from(timestamp / self.era_seconds + self.era_offset)
             .map_err(|_| error!(Errors::CalculationFail))
     }
+
+    pub fn calc_withdrawable_era(&self, unstake_account: &UnstakeAccount) -> u64 {
+        if unstake_account.era == self.catch_up_era {
+            unstake_account.withdrawable_era + self.catch_up_shift
+        } else {
+            unstake_account.withdrawable_era
+        }
+    }
+
+    // staking tokens are back with the stake manager once nothing is unbonding anymore or an
+    // unstake account of the same era would have been withdrawable by now. Cancelled unstakes
+    // are bonded again, the buffer refill becomes spendable. Until era_unbond ran, this era's
+    // unbond is not part of unbonding yet.
+    pub fn release_landed_unbonds(&mut self) {
+        let all_landed =
+            self.unbonding == 0 && !self.is_migrating() && self.era_status != EraStatus::EraUpdated;
+        if self.pending_rebond > 0 && (all_landed || self.latest_era >= self.pending_rebond_era) {
+            self.pending_bond += self.pending_rebond;
+            self.pending_rebond = 0;
+        }
+        if self.buffer_unbonding > 0 && (all_landed || self.latest_era >= self.buffer_unbonding_era)
+        {
+            self.buffer += self.buffer_unbonding;
+            self.buffer_unbonding = 0;
+        }
+    }
+
+    pub fn is_migrating(&self) -> bool {
+        self.migration_staking_pool != Pubkey::default()
+    }
+
+    // bonds already go to the new staking pool while migrating
+    pub fn bond_staking_pool(&self) -> Pubkey {
+        if self.is_migrating() {
+            self.migration_staking_pool
+        } else {
+            self.staking_pool
+        }
+    }
+
+    // unbonds come from the new staking pool once the migration bonded into it, the old stake
+    // may already be drained by migrate_unbond
+    pub fn unbond_staking_pool(&self) -> Pubkey {
+        if self.is_migrating() && self.migration_bonded {
+            self.migration_staking_pool
+        } else {
+            self.staking_pool
+        }
+    }
+
+    pub fn unbond_staking_program(&self) -> Pubkey {
+        if self.is_migrating() && self.migration_bonded {
+            self.migration_staking_program
+        } else {
+            self.staking_program
+        }
+    }
+
+    pub fn bond_staking_program(&self) -> Pubkey {
+        if self.is_migrating() {
+            self.migration_staking_program
+        } else {
+            self.staking_program
+        }
+    }
+
+    // unstake accounts created by the migration become withdrawable no earlier than this
+    pub fn calc_migration_withdrawable_timestamp(&self, unbonding_seconds: u64) -> u64 {
+        self.migration_started_at as u64 + unbonding_seconds
+    }
+
+    pub fn is_paused(&self, operation: u32) -> bool {
+        self.paused & operation != 0
+    }
+
+    pub fn check_not_paused(&self, operation: u32) -> Result<()> {
+        require!(!self.is_paused(operation), Errors::Paused);
+
+        Ok(())
+    }
+
+    pub fn check_era_and_deadline(
+        &self,
+        expected_era: Option<u64>,
+        deadline: Option<i64>,
+    ) -> Result<()> {
+        if let Some(expected_era) = expected_era {
+            require_eq!(self.latest_era, expected_era, Errors::EraNotMatch);
+            // the rate of the expected era is only final once its active is updated
+            require!(
+                self.era_status == EraStatus::ActiveUpdated,
+                Errors::EraNotMatch
+            );
+        }
+
+        if let Some(deadline) = deadline {
+            let timestamp = Clock::get()?.unix_timestamp;
+            require_gte!(deadline, timestamp, Errors::DeadlineExceeded);
+        }
+
+        Ok(())
+    }
 }
 
 #[account]
@@ -115,7 +474,52 @@ pub struct UnstakeAccount {
     pub user: Pubkey,
     pub amount: u64,
     pub withdrawable_era: u64,
+    pub era: u64,
+    pub nonce: u64,
+    pub bump: u8,
+    pub ticket_mint: Pubkey, // default if no withdrawal ticket was minted
 
     /// Reserved space for future upgrades. Do not use.
-    pub _reserved: [u8; 128],
+    pub _reserved: [u8; 79], // era, nonce, bump and ticket_mint took 49 of the original 128 bytes
+}
+
+impl UnstakeAccount {
+    // derived from the unstake nonce, as opposed to the keypair accounts from before versioning
+    pub fn is_pda(&self, address: Pubkey) -> bool {
+        Pubkey::create_program_address(
+            &[
+                helper::UNSTAKE_ACCOUNT_SEED,
+                &self.stake_manager.to_bytes(),
+                &self.user.to_bytes(),
+                &self.nonce.to_le_bytes(),
+                &[self.bump],
+            ],
+            &crate::ID,
+        )
+        .is_ok_and(|pda| pda == address)
+    }
+}
+
+#[account]
+#[derive(Debug)]
+pub struct UnstakeNonceAccount {
+    pub stake_manager: Pubkey,
+    pub user: Pubkey,
+    pub nonce: u64,
+}
+
+// written once by era_active and never changed afterwards
+#[account]
+#[derive(Debug)]
+pub struct EraSnapshot {
+    pub stake_manager: Pubkey,
+    pub era: u64,
+    pub rate: u64, // decimals 9
+    pub active: u64,
+    pub lsd_token_supply: u64,
+    pub reward: u64,
+    pub platform_fee: u64,
+    pub bond_amount: u64,
+    pub unbond_amount: u64,
+    pub timestamp: i64,
 }
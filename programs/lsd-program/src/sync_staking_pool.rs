@@ -0,0 +1,53 @@
+use crate::{Errors, StakeManager};
+use anchor_lang::prelude::*;
+use staking_program;
+
+#[derive(Accounts)]
+pub struct SyncStakingPool<'info> {
+    #[account(mut)]
+    pub stake_manager: Box<Account<'info, StakeManager>>,
+
+    #[account(
+        address = stake_manager.staking_pool @Errors::SpStakePoolNotMatch,
+    )]
+    pub staking_pool: Box<Account<'info, staking_program::StakingPool>>,
+}
+
+#[event]
+pub struct EventSyncStakingPool {
+    pub stake_manager: Pubkey,
+    pub staking_pool: Pubkey,
+    pub old_staking_min_stake_amount: u64,
+    pub new_staking_min_stake_amount: u64,
+    pub old_unbonding_duration: u64,
+    pub new_unbonding_duration: u64,
+}
+
+impl<'info> SyncStakingPool<'info> {
+    pub fn process(&mut self) -> Result<()> {
+        require!(
+            !self.stake_manager.is_migrating(),
+            Errors::StakingPoolMigrating
+        );
+
+        let new_staking_min_stake_amount = self.staking_pool.min_stake_amount;
+        let new_unbonding_duration =
+            self.staking_pool.unbonding_seconds / self.stake_manager.era_seconds as u64 + 1;
+
+        let old_staking_min_stake_amount = self.stake_manager.staking_min_stake_amount;
+        let old_unbonding_duration = self.stake_manager.unbonding_duration;
+
+        self.stake_manager.staking_min_stake_amount = new_staking_min_stake_amount;
+        self.stake_manager.unbonding_duration = new_unbonding_duration;
+
+        emit!(EventSyncStakingPool {
+            stake_manager: self.stake_manager.key(),
+            staking_pool: self.staking_pool.key(),
+            old_staking_min_stake_amount,
+            new_staking_min_stake_amount,
+            old_unbonding_duration,
+            new_unbonding_duration,
+        });
+        Ok(())
+    }
+}
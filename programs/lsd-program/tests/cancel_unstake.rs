@@ -0,0 +1,136 @@
+mod common;
+
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::spl_token;
+use common::{TestEnv, UNBONDING_DURATION};
+use lsd_program::{helper, EraStatus};
+use solana_program_test::tokio;
+use solana_sdk::signature::{Keypair, Signer};
+
+const AMOUNT: u64 = 1_000_000_000;
+
+fn cancel_unstake_accounts(
+    env: &TestEnv,
+    user: Pubkey,
+    unstake_account: Pubkey,
+) -> lsd_program::accounts::CancelUnstake {
+    lsd_program::accounts::CancelUnstake {
+        user,
+        rent_payer: env.payer(),
+        stake_manager: env.stake_manager,
+        unstake_account,
+        lsd_token_mint: env.lsd_token_mint,
+        user_lsd_token_account: get_associated_token_address(&user, &env.lsd_token_mint),
+        token_program: spl_token::ID,
+        associated_token_program: anchor_spl::associated_token::ID,
+        system_program: anchor_lang::system_program::ID,
+    }
+}
+
+fn era_new_accounts(env: &TestEnv) -> lsd_program::accounts::EraNew {
+    lsd_program::accounts::EraNew {
+        stake_manager: env.stake_manager,
+    }
+}
+
+#[tokio::test]
+async fn cancel_in_the_unstake_era_reverts_the_era_unbond() {
+    let mut env = TestEnv::new(100).await;
+    let user = Keypair::new();
+    let unstake_account =
+        env.set_unstake_account(user.pubkey(), 0, AMOUNT, 100, 100 + UNBONDING_DURATION);
+    env.update_stake_manager(|stake_manager| {
+        stake_manager.era_unbond = AMOUNT;
+        stake_manager.pending_unstake_amount = AMOUNT;
+    })
+    .await;
+
+    let accounts = cancel_unstake_accounts(&env, user.pubkey(), unstake_account);
+    env.send(
+        accounts,
+        lsd_program::instruction::CancelUnstake {},
+        &[&user],
+    )
+    .await
+    .unwrap();
+
+    let stake_manager = env.stake_manager().await;
+    assert_eq!(stake_manager.era_unbond, 0);
+    assert_eq!(stake_manager.active, AMOUNT);
+    assert_eq!(
+        env.token_balance(env.lsd_token_mint, user.pubkey()).await,
+        Some(AMOUNT)
+    );
+    assert!(!env.account_exists(unstake_account).await);
+}
+
+#[tokio::test]
+async fn cancel_during_the_unbond_rebonds_once_it_landed() {
+    let mut env = TestEnv::new(100).await;
+    let user = Keypair::new();
+    let withdrawable_era = 99 + UNBONDING_DURATION;
+    let unstake_account = env.set_unstake_account(user.pubkey(), 0, AMOUNT, 99, withdrawable_era);
+    env.set_mint(env.lsd_token_mint, env.stake_manager, 2 * AMOUNT);
+    env.update_stake_manager(|stake_manager| {
+        stake_manager.active = 2 * AMOUNT;
+        stake_manager.unbonding = AMOUNT;
+        stake_manager.pending_unstake_amount = AMOUNT;
+    })
+    .await;
+
+    // netted by the cycle of era 99, its unbond is still in flight
+    let accounts = cancel_unstake_accounts(&env, user.pubkey(), unstake_account);
+    env.send(
+        accounts,
+        lsd_program::instruction::CancelUnstake {},
+        &[&user],
+    )
+    .await
+    .unwrap();
+
+    let stake_manager = env.stake_manager().await;
+    assert_eq!(stake_manager.rate, helper::DEFAULT_RATE);
+    assert_eq!(stake_manager.active, 3 * AMOUNT);
+    assert_eq!(stake_manager.pending_rebond, AMOUNT);
+    assert_eq!(stake_manager.pending_rebond_era, withdrawable_era);
+    assert_eq!(stake_manager.pending_bond, 0);
+    assert_eq!(stake_manager.pending_unstake_amount, 0);
+    assert_eq!(
+        env.token_balance(env.lsd_token_mint, user.pubkey()).await,
+        Some(AMOUNT)
+    );
+    assert!(!env.account_exists(unstake_account).await);
+
+    // not bonded while the staking tokens are still unbonding
+    env.warp_to_era(101).await;
+    env.send(
+        era_new_accounts(&env),
+        lsd_program::instruction::EraNew {},
+        &[],
+    )
+    .await
+    .unwrap();
+    let stake_manager = env.stake_manager().await;
+    assert_eq!(stake_manager.pending_rebond, AMOUNT);
+    assert_eq!(stake_manager.pending_bond, 0);
+
+    // era_withdraw landed the unbond
+    env.update_stake_manager(|stake_manager| {
+        stake_manager.era_status = EraStatus::ActiveUpdated;
+        stake_manager.unbonding = 0;
+    })
+    .await;
+    env.warp_to_era(102).await;
+    env.send(
+        era_new_accounts(&env),
+        lsd_program::instruction::EraNew {},
+        &[],
+    )
+    .await
+    .unwrap();
+    let stake_manager = env.stake_manager().await;
+    assert_eq!(stake_manager.pending_rebond, 0);
+    assert_eq!(stake_manager.pending_bond, AMOUNT);
+    assert_eq!(stake_manager.active, 3 * AMOUNT);
+}
@@ -0,0 +1,188 @@
+mod common;
+
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::spl_token;
+use common::{assert_error, TestEnv};
+use lsd_program::Errors;
+use solana_program_test::tokio;
+use solana_sdk::signature::Signer;
+
+const AMOUNT: u64 = 1_000_000_000;
+const DUST: u64 = 3;
+
+fn close_stake_manager_accounts(
+    env: &TestEnv,
+    staking_stake_account: Pubkey,
+) -> lsd_program::accounts::CloseStakeManager {
+    lsd_program::accounts::CloseStakeManager {
+        admin: env.admin.pubkey(),
+        stake_manager: env.stake_manager,
+        lsd_token_mint: env.lsd_token_mint,
+        staking_token_mint: env.staking_token_mint,
+        admin_staking_token_account: get_associated_token_address(
+            &env.admin.pubkey(),
+            &env.staking_token_mint,
+        ),
+        stake_manager_staking_token_account: get_associated_token_address(
+            &env.stake_manager,
+            &env.staking_token_mint,
+        ),
+        staking_stake_account,
+        token_program: spl_token::ID,
+        associated_token_program: anchor_spl::associated_token::ID,
+        system_program: anchor_lang::system_program::ID,
+    }
+}
+
+fn clear_legacy_unstake_accounts_accounts(
+    env: &TestEnv,
+) -> lsd_program::accounts::ClearLegacyUnstakeAccounts {
+    lsd_program::accounts::ClearLegacyUnstakeAccounts {
+        admin: env.admin.pubkey(),
+        stake_manager: env.stake_manager,
+    }
+}
+
+#[tokio::test]
+async fn close_waits_for_outstanding_unstakes_and_unbonds() {
+    let mut env = TestEnv::new(100).await;
+    let admin = env.admin.insecure_clone();
+    env.set_token_account(env.staking_token_mint, admin.pubkey(), 0);
+    env.set_token_account(env.staking_token_mint, env.stake_manager, AMOUNT + DUST);
+    let staking_stake_account = env.set_staking_stake_account(0);
+    env.update_stake_manager(|stake_manager| {
+        stake_manager.wind_down = true;
+        stake_manager.pending_unstake_amount = AMOUNT;
+    })
+    .await;
+
+    // an unstake account still holds a claim on the pool
+    let accounts = close_stake_manager_accounts(&env, staking_stake_account);
+    let result = env
+        .send(
+            accounts,
+            lsd_program::instruction::CloseStakeManager {},
+            &[&admin],
+        )
+        .await;
+    assert_error(result, Errors::StakeManagerNotEmpty);
+
+    // its unbond is still in flight
+    env.set_token_account(env.staking_token_mint, env.stake_manager, DUST);
+    env.update_stake_manager(|stake_manager| stake_manager.unbonding = AMOUNT)
+        .await;
+    let accounts = close_stake_manager_accounts(&env, staking_stake_account);
+    let result = env
+        .send(
+            accounts,
+            lsd_program::instruction::CloseStakeManager {},
+            &[&admin],
+        )
+        .await;
+    assert_error(result, Errors::StakeManagerNotEmpty);
+
+    // the staking pool still holds a stake
+    env.update_stake_manager(|stake_manager| {
+        stake_manager.pending_unstake_amount = 0;
+        stake_manager.unbonding = 0;
+    })
+    .await;
+    let staked_account = env.set_staking_stake_account(AMOUNT);
+    let accounts = close_stake_manager_accounts(&env, staked_account);
+    let result = env
+        .send(
+            accounts,
+            lsd_program::instruction::CloseStakeManager {},
+            &[&admin],
+        )
+        .await;
+    assert_error(result, Errors::StakeManagerNotEmpty);
+
+    // keypair unstake accounts from before versioning can't be counted
+    env.update_stake_manager(|stake_manager| stake_manager.legacy_unstake_accounts = true)
+        .await;
+    let accounts = close_stake_manager_accounts(&env, staking_stake_account);
+    let result = env
+        .send(
+            accounts,
+            lsd_program::instruction::CloseStakeManager {},
+            &[&admin],
+        )
+        .await;
+    assert_error(result, Errors::LegacyUnstakeAccounts);
+
+    env.update_stake_manager(|stake_manager| stake_manager.legacy_unstake_accounts = false)
+        .await;
+    let accounts = close_stake_manager_accounts(&env, staking_stake_account);
+    env.send(
+        accounts,
+        lsd_program::instruction::CloseStakeManager {},
+        &[&admin],
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        env.token_balance(env.staking_token_mint, admin.pubkey())
+            .await,
+        Some(DUST)
+    );
+    assert!(!env.account_exists(env.stake_manager).await);
+}
+
+#[tokio::test]
+async fn close_after_legacy_unstake_accounts_matured() {
+    let mut env = TestEnv::new(100).await;
+    let admin = env.admin.insecure_clone();
+    env.set_token_account(env.staking_token_mint, admin.pubkey(), 0);
+    let staking_stake_account = env.set_staking_stake_account(0);
+    env.update_stake_manager(|stake_manager| {
+        stake_manager.legacy_unstake_accounts = true;
+        stake_manager.legacy_withdrawable_era = 102;
+    })
+    .await;
+
+    // withdraw_legacy stays open until the stake manager winds down
+    let result = env
+        .send(
+            clear_legacy_unstake_accounts_accounts(&env),
+            lsd_program::instruction::ClearLegacyUnstakeAccounts {},
+            &[&admin],
+        )
+        .await;
+    assert_error(result, Errors::NotWindDown);
+
+    // the last keypair unstake accounts only mature in era 102
+    env.update_stake_manager(|stake_manager| stake_manager.wind_down = true)
+        .await;
+    let result = env
+        .send(
+            clear_legacy_unstake_accounts_accounts(&env),
+            lsd_program::instruction::ClearLegacyUnstakeAccounts {},
+            &[&admin],
+        )
+        .await;
+    assert_error(result, Errors::LegacyUnstakeAccounts);
+
+    env.update_stake_manager(|stake_manager| stake_manager.latest_era = 102)
+        .await;
+    env.send(
+        clear_legacy_unstake_accounts_accounts(&env),
+        lsd_program::instruction::ClearLegacyUnstakeAccounts {},
+        &[&admin],
+    )
+    .await
+    .unwrap();
+    assert!(!env.stake_manager().await.legacy_unstake_accounts);
+
+    let accounts = close_stake_manager_accounts(&env, staking_stake_account);
+    env.send(
+        accounts,
+        lsd_program::instruction::CloseStakeManager {},
+        &[&admin],
+    )
+    .await
+    .unwrap();
+    assert!(!env.account_exists(env.stake_manager).await);
+}
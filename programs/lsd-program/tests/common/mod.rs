@@ -0,0 +1,317 @@
+#![allow(dead_code)]
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::{AccountDeserialize, AccountSerialize, InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::spl_token;
+use lsd_program::{helper, EraStatus, StakeManager, UnstakeAccount};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::account::{Account, AccountSharedData};
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transaction::{Transaction, TransactionError};
+
+pub const ERA_SECONDS: i64 = 86_400;
+pub const UNBONDING_DURATION: u64 = 3;
+
+// anchor's entry wants the accounts to outlive the instruction
+fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> anchor_lang::solana_program::entrypoint::ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    lsd_program::entry(program_id, accounts, data)
+}
+
+pub struct TestEnv {
+    pub ctx: ProgramTestContext,
+    pub admin: Keypair,
+    pub stake_manager: Pubkey,
+    pub lsd_token_mint: Pubkey,
+    pub staking_token_mint: Pubkey,
+    pub staking_pool: Pubkey,
+}
+
+impl TestEnv {
+    // a stake manager in ActiveUpdated at latest_era, with the clock inside latest_era
+    pub async fn new(latest_era: u64) -> Self {
+        let program_test = ProgramTest::new(
+            "lsd_program",
+            lsd_program::ID,
+            processor!(process_instruction),
+        );
+        let ctx = program_test.start_with_context().await;
+
+        let admin = Keypair::new();
+        let (stake_manager, pool_seed_bump) = Pubkey::find_program_address(
+            &[helper::STAKE_MANAGER_SEED, &admin.pubkey().to_bytes(), &[0]],
+            &lsd_program::ID,
+        );
+        let mut env = Self {
+            ctx,
+            admin,
+            stake_manager,
+            lsd_token_mint: Pubkey::new_unique(),
+            staking_token_mint: Pubkey::new_unique(),
+            staking_pool: Pubkey::new_unique(),
+        };
+
+        env.set_mint(env.lsd_token_mint, stake_manager, 0);
+        env.set_mint(env.staking_token_mint, Pubkey::new_unique(), 0);
+        env.set_token_account(env.staking_token_mint, stake_manager, 0);
+        env.set_token_account(env.lsd_token_mint, env.admin.pubkey(), 0);
+
+        let zeroed = vec![0u8; helper::STAKE_MANAGER_SPACE];
+        let mut state = StakeManager::try_deserialize_unchecked(&mut zeroed.as_slice()).unwrap();
+        state.creator = env.admin.pubkey();
+        state.admin = env.admin.pubkey();
+        state.pool_seed_bump = pool_seed_bump;
+        state.lsd_token_mint = env.lsd_token_mint;
+        state.staking_token_mint = env.staking_token_mint;
+        state.staking_program = staking_program::ID;
+        state.staking_pool = env.staking_pool;
+        state.era_seconds = ERA_SECONDS;
+        state.unbonding_duration = UNBONDING_DURATION;
+        state.era_status = EraStatus::ActiveUpdated;
+        state.latest_era = latest_era;
+        state.rate = helper::DEFAULT_RATE;
+        state.fee_recipient = env.admin.pubkey();
+        state.version = helper::STAKE_MANAGER_VERSION;
+        env.set_anchor_account(stake_manager, &state, helper::STAKE_MANAGER_SPACE);
+
+        env.warp_to_era(latest_era).await;
+        env
+    }
+
+    pub fn set_account(&mut self, address: Pubkey, owner: Pubkey, data: Vec<u8>) {
+        let account = Account {
+            lamports: 1_000_000_000,
+            data,
+            owner,
+            executable: false,
+            rent_epoch: 0,
+        };
+        self.ctx
+            .set_account(&address, &AccountSharedData::from(account));
+    }
+
+    // data is padded with zeros up to space
+    pub fn set_anchor_account<T: AccountSerialize>(
+        &mut self,
+        address: Pubkey,
+        state: &T,
+        space: usize,
+    ) {
+        let mut data = vec![];
+        state.try_serialize(&mut data).unwrap();
+        assert!(data.len() <= space);
+        data.resize(space, 0);
+        self.set_account(address, lsd_program::ID, data);
+    }
+
+    pub async fn anchor_account<T: AccountDeserialize>(&mut self, address: Pubkey) -> Option<T> {
+        let account = self.ctx.banks_client.get_account(address).await.unwrap()?;
+        Some(T::try_deserialize(&mut account.data.as_slice()).unwrap())
+    }
+
+    pub async fn stake_manager(&mut self) -> StakeManager {
+        self.anchor_account(self.stake_manager).await.unwrap()
+    }
+
+    pub async fn update_stake_manager(&mut self, f: impl FnOnce(&mut StakeManager)) {
+        let mut state = self.stake_manager().await;
+        f(&mut state);
+        self.set_anchor_account(self.stake_manager, &state, helper::STAKE_MANAGER_SPACE);
+    }
+
+    pub fn set_mint(&mut self, address: Pubkey, authority: Pubkey, supply: u64) {
+        let mut data = vec![0u8; spl_token::state::Mint::LEN];
+        spl_token::state::Mint {
+            mint_authority: COption::Some(authority),
+            supply,
+            decimals: 9,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        }
+        .pack_into_slice(&mut data);
+        self.set_account(address, spl_token::ID, data);
+    }
+
+    // associated token account of owner
+    pub fn set_token_account(&mut self, mint: Pubkey, owner: Pubkey, amount: u64) -> Pubkey {
+        let address = get_associated_token_address(&owner, &mint);
+        let mut data = vec![0u8; spl_token::state::Account::LEN];
+        spl_token::state::Account {
+            mint,
+            owner,
+            amount,
+            state: spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        }
+        .pack_into_slice(&mut data);
+        self.set_account(address, spl_token::ID, data);
+        address
+    }
+
+    pub fn set_staking_pool(&mut self, address: Pubkey, unbonding_seconds: u64) {
+        let zeroed = vec![0u8; 256];
+        let mut state =
+            staking_program::StakingPool::try_deserialize_unchecked(&mut zeroed.as_slice())
+                .unwrap();
+        state.token_mint = self.staking_token_mint;
+        state.unbonding_seconds = unbonding_seconds;
+        let mut data = vec![];
+        state.try_serialize(&mut data).unwrap();
+        self.set_account(address, staking_program::ID, data);
+    }
+
+    // stake account of the stake manager in the staking pool, owned by the staking program
+    pub fn set_staking_stake_account(&mut self, amount: u64) -> Pubkey {
+        let address = Pubkey::new_unique();
+        let zeroed = vec![0u8; 256];
+        let mut state =
+            staking_program::StakeAccount::try_deserialize_unchecked(&mut zeroed.as_slice())
+                .unwrap();
+        state.staking_pool = self.staking_pool;
+        state.user = self.stake_manager;
+        state.amount = amount;
+        let mut data = vec![];
+        state.try_serialize(&mut data).unwrap();
+        self.set_account(address, staking_program::ID, data);
+        address
+    }
+
+    pub async fn token_balance(&mut self, mint: Pubkey, owner: Pubkey) -> Option<u64> {
+        let address = get_associated_token_address(&owner, &mint);
+        let account = self.ctx.banks_client.get_account(address).await.unwrap()?;
+        Some(
+            spl_token::state::Account::unpack(&account.data)
+                .unwrap()
+                .amount,
+        )
+    }
+
+    pub async fn account_exists(&mut self, address: Pubkey) -> bool {
+        self.ctx
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .is_some()
+    }
+
+    // moves the clock into the middle of era, the stake manager era_offset is 0
+    pub async fn warp_to_era(&mut self, era: u64) {
+        let mut clock: Clock = self.ctx.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp = era as i64 * ERA_SECONDS + ERA_SECONDS / 2;
+        self.ctx.set_sysvar(&clock);
+    }
+
+    pub async fn send(
+        &mut self,
+        accounts: impl ToAccountMetas,
+        data: impl InstructionData,
+        signers: &[&Keypair],
+    ) -> std::result::Result<(), BanksClientError> {
+        let instruction = Instruction {
+            program_id: lsd_program::ID,
+            accounts: accounts.to_account_metas(None),
+            data: data.data(),
+        };
+        let blockhash = self.ctx.get_new_latest_blockhash().await.unwrap();
+        let mut all_signers = vec![&self.ctx.payer];
+        all_signers.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&self.ctx.payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+        self.ctx.banks_client.process_transaction(transaction).await
+    }
+
+    pub fn payer(&self) -> Pubkey {
+        self.ctx.payer.pubkey()
+    }
+}
+
+pub fn assert_error(result: std::result::Result<(), BanksClientError>, code: impl Into<u32>) {
+    match result.unwrap_err().unwrap() {
+        TransactionError::InstructionError(_, InstructionError::Custom(actual)) => {
+            assert_eq!(actual, code.into())
+        }
+        err => panic!("unexpected error {err:?}"),
+    }
+}
+
+impl TestEnv {
+    pub fn unstake_account_address(&self, user: Pubkey, nonce: u64) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                helper::UNSTAKE_ACCOUNT_SEED,
+                &self.stake_manager.to_bytes(),
+                &user.to_bytes(),
+                &nonce.to_le_bytes(),
+            ],
+            &lsd_program::ID,
+        )
+    }
+
+    // pda unstake account as created by unstake
+    pub fn set_unstake_account(
+        &mut self,
+        user: Pubkey,
+        nonce: u64,
+        amount: u64,
+        era: u64,
+        withdrawable_era: u64,
+    ) -> Pubkey {
+        let (address, bump) = self.unstake_account_address(user, nonce);
+        let unstake_account = UnstakeAccount {
+            stake_manager: self.stake_manager,
+            user,
+            amount,
+            withdrawable_era,
+            era,
+            nonce,
+            bump,
+            ticket_mint: Pubkey::default(),
+            _reserved: [0u8; 79],
+        };
+        self.set_anchor_account(
+            address,
+            &unstake_account,
+            8 + std::mem::size_of::<UnstakeAccount>(),
+        );
+        address
+    }
+
+    pub fn withdraw_accounts(
+        &self,
+        user: Pubkey,
+        unstake_account: Pubkey,
+    ) -> lsd_program::accounts::Withdraw {
+        lsd_program::accounts::Withdraw {
+            user,
+            rent_payer: self.payer(),
+            stake_manager: self.stake_manager,
+            unstake_account,
+            staking_token_mint: self.staking_token_mint,
+            user_staking_token_account: get_associated_token_address(
+                &user,
+                &self.staking_token_mint,
+            ),
+            stake_manager_staking_token_account: get_associated_token_address(
+                &self.stake_manager,
+                &self.staking_token_mint,
+            ),
+            token_program: spl_token::ID,
+            associated_token_program: anchor_spl::associated_token::ID,
+            system_program: anchor_lang::system_program::ID,
+        }
+    }
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use common::{assert_error, TestEnv, UNBONDING_DURATION};
+use lsd_program::{EraStatus, Errors};
+use solana_program_test::tokio;
+use solana_sdk::signature::{Keypair, Signer};
+
+const AMOUNT: u64 = 1_000_000_000;
+
+fn era_catch_up_accounts(env: &TestEnv) -> lsd_program::accounts::EraCatchUp {
+    lsd_program::accounts::EraCatchUp {
+        stake_manager: env.stake_manager,
+    }
+}
+
+#[tokio::test]
+async fn withdraw_after_catch_up() {
+    let mut env = TestEnv::new(100).await;
+    let user = Keypair::new();
+    let earlier = env.set_unstake_account(user.pubkey(), 0, AMOUNT, 99, 99 + UNBONDING_DURATION);
+    let late = env.set_unstake_account(user.pubkey(), 1, AMOUNT, 100, 100 + UNBONDING_DURATION);
+    env.set_token_account(env.staking_token_mint, env.stake_manager, 2 * AMOUNT);
+    env.update_stake_manager(|stake_manager| stake_manager.pending_unstake_amount = 2 * AMOUNT)
+        .await;
+
+    // keepers were offline for eras 101 to 104
+    env.warp_to_era(105).await;
+    env.send(
+        era_catch_up_accounts(&env),
+        lsd_program::instruction::EraCatchUp {},
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let stake_manager = env.stake_manager().await;
+    assert_eq!(stake_manager.latest_era, 105);
+    assert_eq!(stake_manager.catch_up_era, 100);
+    assert_eq!(stake_manager.catch_up_shift, 4);
+    assert_eq!(
+        stake_manager.catch_up_unbonding_duration,
+        UNBONDING_DURATION
+    );
+
+    // unbonded by the cycle of era 100 as planned
+    let accounts = env.withdraw_accounts(user.pubkey(), earlier);
+    env.send(accounts, lsd_program::instruction::Withdraw {}, &[&user])
+        .await
+        .unwrap();
+    assert_eq!(
+        env.token_balance(env.staking_token_mint, user.pubkey())
+            .await,
+        Some(AMOUNT)
+    );
+
+    // unstaked during era 100, only unbonded by the catch up cycle
+    let accounts = env.withdraw_accounts(user.pubkey(), late);
+    let result = env
+        .send(accounts, lsd_program::instruction::Withdraw {}, &[&user])
+        .await;
+    assert_error(result, Errors::UnstakeAccountNotWithdrawable);
+
+    // a shorter unbonding_duration must not let another catch up drop the shift early
+    env.update_stake_manager(|stake_manager| {
+        stake_manager.era_status = EraStatus::ActiveUpdated;
+        stake_manager.unbonding_duration = 1;
+    })
+    .await;
+    env.warp_to_era(107).await;
+    let result = env
+        .send(
+            era_catch_up_accounts(&env),
+            lsd_program::instruction::EraCatchUp {},
+            &[],
+        )
+        .await;
+    assert_error(result, Errors::EraCatchUpPending);
+
+    // the regular cycles reached the shifted withdrawable era
+    env.update_stake_manager(|stake_manager| stake_manager.latest_era = 107)
+        .await;
+    let accounts = env.withdraw_accounts(user.pubkey(), late);
+    env.send(accounts, lsd_program::instruction::Withdraw {}, &[&user])
+        .await
+        .unwrap();
+    assert_eq!(
+        env.token_balance(env.staking_token_mint, user.pubkey())
+            .await,
+        Some(2 * AMOUNT)
+    );
+    assert_eq!(env.stake_manager().await.pending_unstake_amount, 0);
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use common::{TestEnv, UNBONDING_DURATION};
+use lsd_program::EraStatus;
+use solana_program_test::tokio;
+
+const AMOUNT: u64 = 1_000_000_000;
+
+fn era_new_accounts(env: &TestEnv) -> lsd_program::accounts::EraNew {
+    lsd_program::accounts::EraNew {
+        stake_manager: env.stake_manager,
+    }
+}
+
+#[tokio::test]
+async fn buffer_refills_under_net_unstakes() {
+    let mut env = TestEnv::new(100).await;
+    env.update_stake_manager(|stake_manager| {
+        stake_manager.active = 10 * AMOUNT;
+        stake_manager.buffer_target_bps = 1_000;
+        stake_manager.era_unbond = 2 * AMOUNT;
+    })
+    .await;
+
+    // instant unstakes drained the buffer and there are no deposits to refill it from
+    env.warp_to_era(101).await;
+    env.send(
+        era_new_accounts(&env),
+        lsd_program::instruction::EraNew {},
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let stake_manager = env.stake_manager().await;
+    assert_eq!(stake_manager.era_status, EraStatus::EraUpdated);
+    assert_eq!(stake_manager.pending_unbond, 3 * AMOUNT);
+    assert_eq!(stake_manager.buffer, 0);
+    assert_eq!(stake_manager.buffer_unbonding, AMOUNT);
+    assert_eq!(stake_manager.buffer_unbonding_era, 101 + UNBONDING_DURATION);
+
+    // era_unbond sent it to the staking pool and era_withdraw landed it
+    env.update_stake_manager(|stake_manager| {
+        stake_manager.era_status = EraStatus::ActiveUpdated;
+        stake_manager.pending_unbond = 0;
+    })
+    .await;
+    env.warp_to_era(102).await;
+    env.send(
+        era_new_accounts(&env),
+        lsd_program::instruction::EraNew {},
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let stake_manager = env.stake_manager().await;
+    assert_eq!(stake_manager.buffer, AMOUNT);
+    assert_eq!(stake_manager.buffer_unbonding, 0);
+    assert_eq!(stake_manager.pending_unbond, 0);
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use anchor_lang::prelude::*;
+use common::{assert_error, TestEnv};
+use lsd_program::Errors;
+use solana_program_test::tokio;
+use solana_sdk::signature::Signer;
+
+fn renounce_admin_accounts(env: &TestEnv) -> lsd_program::accounts::RenounceAdmin {
+    lsd_program::accounts::RenounceAdmin {
+        admin: env.admin.pubkey(),
+        stake_manager: env.stake_manager,
+    }
+}
+
+#[tokio::test]
+async fn renounce_waits_for_the_migration_and_keeps_a_fee_recipient() {
+    let mut env = TestEnv::new(100).await;
+    let admin = env.admin.insecure_clone();
+    env.update_stake_manager(|stake_manager| {
+        stake_manager.migration_staking_pool = Pubkey::new_unique();
+        stake_manager.fee_recipient = Pubkey::default();
+    })
+    .await;
+
+    // only the admin can finish the migration
+    let result = env
+        .send(
+            renounce_admin_accounts(&env),
+            lsd_program::instruction::RenounceAdmin {},
+            &[&admin],
+        )
+        .await;
+    assert_error(result, Errors::StakingPoolMigrating);
+
+    env.update_stake_manager(|stake_manager| {
+        stake_manager.migration_staking_pool = Pubkey::default();
+    })
+    .await;
+    env.send(
+        renounce_admin_accounts(&env),
+        lsd_program::instruction::RenounceAdmin {},
+        &[&admin],
+    )
+    .await
+    .unwrap();
+
+    let stake_manager = env.stake_manager().await;
+    assert!(stake_manager.renounced);
+    assert_eq!(stake_manager.admin, Pubkey::default());
+    assert_eq!(stake_manager.fee_recipient, admin.pubkey());
+    assert_eq!(stake_manager.fee_recipient_or_admin(), admin.pubkey());
+}
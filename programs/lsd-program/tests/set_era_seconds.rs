@@ -0,0 +1,51 @@
+mod common;
+
+use common::{assert_error, TestEnv, ERA_SECONDS};
+use lsd_program::Errors;
+use solana_program_test::tokio;
+use solana_sdk::signature::Signer;
+
+fn set_era_seconds_accounts(env: &TestEnv) -> lsd_program::accounts::SetEraSeconds {
+    lsd_program::accounts::SetEraSeconds {
+        admin: env.admin.pubkey(),
+        stake_manager: env.stake_manager,
+        staking_pool: env.staking_pool,
+    }
+}
+
+#[tokio::test]
+async fn shorter_eras_wait_for_legacy_unstake_accounts() {
+    let mut env = TestEnv::new(100).await;
+    let admin = env.admin.insecure_clone();
+    env.set_staking_pool(env.staking_pool, ERA_SECONDS as u64);
+    env.update_stake_manager(|stake_manager| stake_manager.legacy_unstake_accounts = true)
+        .await;
+
+    // keypair unstake accounts from before the upgrade would mature in hours
+    let result = env
+        .send(
+            set_era_seconds_accounts(&env),
+            lsd_program::instruction::SetEraSeconds {
+                new_era_seconds: ERA_SECONDS / 4,
+            },
+            &[&admin],
+        )
+        .await;
+    assert_error(result, Errors::LegacyUnstakeAccounts);
+
+    env.update_stake_manager(|stake_manager| stake_manager.legacy_unstake_accounts = false)
+        .await;
+    env.send(
+        set_era_seconds_accounts(&env),
+        lsd_program::instruction::SetEraSeconds {
+            new_era_seconds: ERA_SECONDS / 4,
+        },
+        &[&admin],
+    )
+    .await
+    .unwrap();
+
+    let stake_manager = env.stake_manager().await;
+    assert_eq!(stake_manager.era_seconds, ERA_SECONDS / 4);
+    assert_eq!(stake_manager.latest_era, 100);
+}
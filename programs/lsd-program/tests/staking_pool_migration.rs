@@ -0,0 +1,75 @@
+mod common;
+
+use anchor_lang::prelude::*;
+use common::{assert_error, TestEnv, ERA_SECONDS};
+use lsd_program::Errors;
+use solana_program_test::tokio;
+use solana_sdk::signature::Signer;
+
+const AMOUNT: u64 = 1_000_000_000;
+
+fn finish_staking_pool_migration_accounts(
+    env: &TestEnv,
+    staking_stake_account: Pubkey,
+    new_staking_pool: Pubkey,
+) -> lsd_program::accounts::FinishStakingPoolMigration {
+    lsd_program::accounts::FinishStakingPoolMigration {
+        admin: env.admin.pubkey(),
+        stake_manager: env.stake_manager,
+        staking_pool: env.staking_pool,
+        staking_stake_account,
+        new_staking_pool,
+    }
+}
+
+#[tokio::test]
+async fn finish_waits_for_the_old_pool_unbonds() {
+    let mut env = TestEnv::new(100).await;
+    let admin = env.admin.insecure_clone();
+    let new_staking_pool = Pubkey::new_unique();
+    env.set_staking_pool(env.staking_pool, ERA_SECONDS as u64);
+    env.set_staking_pool(new_staking_pool, 2 * ERA_SECONDS as u64);
+    let staking_stake_account = env.set_staking_stake_account(0);
+    env.update_stake_manager(|stake_manager| {
+        stake_manager.migration_staking_pool = new_staking_pool;
+        stake_manager.migration_staking_program = staking_program::ID;
+        stake_manager.migration_started_at = 98 * ERA_SECONDS;
+        stake_manager.migration_bonded = true;
+        stake_manager.unbonding = AMOUNT;
+    })
+    .await;
+
+    // an unstake from before the migration is still unbonding from the old pool
+    let accounts =
+        finish_staking_pool_migration_accounts(&env, staking_stake_account, new_staking_pool);
+    let result = env
+        .send(
+            accounts,
+            lsd_program::instruction::FinishStakingPoolMigration {},
+            &[&admin],
+        )
+        .await;
+    assert_error(result, Errors::StakingPoolMigrating);
+
+    // era_withdraw landed it, an unstake after the bond is unbonding from the new pool
+    env.update_stake_manager(|stake_manager| {
+        stake_manager.unbonding = 0;
+        stake_manager.migration_pool_unbonding = AMOUNT / 2;
+    })
+    .await;
+    let accounts =
+        finish_staking_pool_migration_accounts(&env, staking_stake_account, new_staking_pool);
+    env.send(
+        accounts,
+        lsd_program::instruction::FinishStakingPoolMigration {},
+        &[&admin],
+    )
+    .await
+    .unwrap();
+
+    let stake_manager = env.stake_manager().await;
+    assert_eq!(stake_manager.staking_pool, new_staking_pool);
+    assert!(!stake_manager.is_migrating());
+    assert_eq!(stake_manager.unbonding, AMOUNT / 2);
+    assert_eq!(stake_manager.migration_pool_unbonding, 0);
+}
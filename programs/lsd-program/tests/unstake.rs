@@ -0,0 +1,70 @@
+mod common;
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::spl_token;
+use common::{assert_error, TestEnv};
+use lsd_program::{helper, EraStatus, Errors};
+use solana_program_test::tokio;
+use solana_sdk::signature::{Keypair, Signer};
+
+const AMOUNT: u64 = 1_000_000_000;
+
+fn unstake_accounts(
+    env: &TestEnv,
+    user: Pubkey,
+    user_lsd_token_account: Pubkey,
+) -> lsd_program::accounts::Unstake {
+    let (unstake_nonce_account, _) = Pubkey::find_program_address(
+        &[
+            helper::UNSTAKE_NONCE_SEED,
+            &env.stake_manager.to_bytes(),
+            &user.to_bytes(),
+        ],
+        &lsd_program::ID,
+    );
+    lsd_program::accounts::Unstake {
+        user,
+        rent_payer: env.payer(),
+        stake_manager: env.stake_manager,
+        lsd_token_mint: env.lsd_token_mint,
+        user_lsd_token_account,
+        unstake_nonce_account,
+        unstake_account: env.unstake_account_address(user, 0).0,
+        token_program: spl_token::ID,
+        associated_token_program: anchor_spl::associated_token::ID,
+        system_program: anchor_lang::system_program::ID,
+    }
+}
+
+#[tokio::test]
+async fn unstake_rejects_an_era_whose_rate_is_not_final() {
+    let mut env = TestEnv::new(100).await;
+    let user = Keypair::new();
+    env.set_mint(env.lsd_token_mint, env.stake_manager, AMOUNT);
+    let user_lsd_token_account = env.set_token_account(env.lsd_token_mint, user.pubkey(), AMOUNT);
+    env.update_stake_manager(|stake_manager| {
+        stake_manager.active = AMOUNT;
+        stake_manager.era_status = EraStatus::EraUpdated;
+    })
+    .await;
+
+    let unstake = |expected_era| lsd_program::instruction::Unstake {
+        unstake_amount: AMOUNT,
+        min_staking_out: 0,
+        expected_era,
+        deadline: None,
+    };
+
+    // era 100 started but its active is not updated yet
+    let accounts = unstake_accounts(&env, user.pubkey(), user_lsd_token_account);
+    let result = env.send(accounts, unstake(Some(100)), &[&user]).await;
+    assert_error(result, Errors::EraNotMatch);
+
+    env.update_stake_manager(|stake_manager| stake_manager.era_status = EraStatus::ActiveUpdated)
+        .await;
+    let accounts = unstake_accounts(&env, user.pubkey(), user_lsd_token_account);
+    env.send(accounts, unstake(Some(100)), &[&user])
+        .await
+        .unwrap();
+    assert_eq!(env.stake_manager().await.pending_unstake_amount, AMOUNT);
+}
@@ -0,0 +1,171 @@
+mod common;
+
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::spl_token;
+use common::{assert_error, TestEnv, UNBONDING_DURATION};
+use lsd_program::{Errors, UnstakeAccount};
+use solana_program_test::tokio;
+use solana_sdk::signature::{Keypair, Signer};
+
+const LEGACY_UNSTAKE_ACCOUNT_SPACE: usize = 216;
+
+// keypair unstake account as created before era, nonce, bump and ticket_mint existed
+fn legacy_unstake_account(
+    stake_manager: Pubkey,
+    user: Pubkey,
+    amount: u64,
+    withdrawable_era: u64,
+) -> Vec<u8> {
+    let mut data = UnstakeAccount::DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&stake_manager.to_bytes());
+    data.extend_from_slice(&user.to_bytes());
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.extend_from_slice(&withdrawable_era.to_le_bytes());
+    data.resize(LEGACY_UNSTAKE_ACCOUNT_SPACE, 0);
+    data
+}
+
+fn withdraw_legacy_accounts(
+    env: &TestEnv,
+    user: Pubkey,
+    unstake_account: Pubkey,
+) -> lsd_program::accounts::WithdrawLegacy {
+    lsd_program::accounts::WithdrawLegacy {
+        user,
+        rent_payer: env.payer(),
+        stake_manager: env.stake_manager,
+        unstake_account,
+        staking_token_mint: env.staking_token_mint,
+        user_staking_token_account: get_associated_token_address(&user, &env.staking_token_mint),
+        stake_manager_staking_token_account: get_associated_token_address(
+            &env.stake_manager,
+            &env.staking_token_mint,
+        ),
+        token_program: spl_token::ID,
+        associated_token_program: anchor_spl::associated_token::ID,
+        system_program: anchor_lang::system_program::ID,
+    }
+}
+
+#[test]
+fn unstake_account_keeps_its_size() {
+    assert_eq!(
+        8 + std::mem::size_of::<UnstakeAccount>(),
+        LEGACY_UNSTAKE_ACCOUNT_SPACE
+    );
+}
+
+#[tokio::test]
+async fn withdraw_after_upgrade() {
+    let mut env = TestEnv::new(100).await;
+    let user = Keypair::new();
+    let amount = 2_000_000_000;
+    let withdrawable_era = 100 + UNBONDING_DURATION;
+
+    let unstake_account = Pubkey::new_unique();
+    let data = legacy_unstake_account(env.stake_manager, user.pubkey(), amount, withdrawable_era);
+    env.set_account(unstake_account, lsd_program::ID, data);
+    env.set_token_account(env.staking_token_mint, env.stake_manager, amount);
+    env.update_stake_manager(|stake_manager| stake_manager.legacy_unstake_accounts = true)
+        .await;
+
+    let decoded: UnstakeAccount = env.anchor_account(unstake_account).await.unwrap();
+    assert_eq!(decoded.amount, amount);
+    assert_eq!(decoded.withdrawable_era, withdrawable_era);
+    assert_eq!(decoded.ticket_mint, Pubkey::default());
+
+    let accounts = withdraw_legacy_accounts(&env, user.pubkey(), unstake_account);
+    let result = env
+        .send(
+            accounts,
+            lsd_program::instruction::WithdrawLegacy {},
+            &[&user],
+        )
+        .await;
+    assert_error(result, Errors::UnstakeAccountNotWithdrawable);
+
+    env.update_stake_manager(|stake_manager| stake_manager.latest_era = withdrawable_era)
+        .await;
+
+    // the pda path rejects keypair accounts
+    let accounts = env.withdraw_accounts(user.pubkey(), unstake_account);
+    let result = env
+        .send(accounts, lsd_program::instruction::Withdraw {}, &[&user])
+        .await;
+    assert_error(result, anchor_lang::error::ErrorCode::ConstraintSeeds);
+
+    let other = Keypair::new();
+    let accounts = withdraw_legacy_accounts(&env, other.pubkey(), unstake_account);
+    let result = env
+        .send(
+            accounts,
+            lsd_program::instruction::WithdrawLegacy {},
+            &[&other],
+        )
+        .await;
+    assert_error(result, Errors::UnstakeUserNotMatch);
+
+    let accounts = withdraw_legacy_accounts(&env, user.pubkey(), unstake_account);
+    env.send(
+        accounts,
+        lsd_program::instruction::WithdrawLegacy {},
+        &[&user],
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        env.token_balance(env.staking_token_mint, user.pubkey())
+            .await,
+        Some(amount)
+    );
+    assert_eq!(
+        env.token_balance(env.staking_token_mint, env.stake_manager)
+            .await,
+        Some(0)
+    );
+    assert!(!env.account_exists(unstake_account).await);
+}
+
+#[tokio::test]
+async fn withdraw_legacy_rejects_pda_unstake_accounts() {
+    let mut env = TestEnv::new(100).await;
+    let user = Keypair::new();
+    let amount = 2_000_000_000;
+    let unstake_account = env.set_unstake_account(user.pubkey(), 0, amount, 97, 100);
+    env.set_token_account(env.staking_token_mint, env.stake_manager, amount);
+    env.update_stake_manager(|stake_manager| stake_manager.pending_unstake_amount = amount)
+        .await;
+
+    // managers without keypair unstake accounts have no legacy path
+    let accounts = withdraw_legacy_accounts(&env, user.pubkey(), unstake_account);
+    let result = env
+        .send(
+            accounts,
+            lsd_program::instruction::WithdrawLegacy {},
+            &[&user],
+        )
+        .await;
+    assert_error(result, Errors::NoLegacyUnstakeAccounts);
+
+    // pda unstake accounts must go through withdraw to settle pending_unstake_amount
+    env.update_stake_manager(|stake_manager| stake_manager.legacy_unstake_accounts = true)
+        .await;
+    let accounts = withdraw_legacy_accounts(&env, user.pubkey(), unstake_account);
+    let result = env
+        .send(
+            accounts,
+            lsd_program::instruction::WithdrawLegacy {},
+            &[&user],
+        )
+        .await;
+    assert_error(result, Errors::InvalidUnstakeAccount);
+
+    let accounts = env.withdraw_accounts(user.pubkey(), unstake_account);
+    env.send(accounts, lsd_program::instruction::Withdraw {}, &[&user])
+        .await
+        .unwrap();
+    assert_eq!(env.stake_manager().await.pending_unstake_amount, 0);
+}